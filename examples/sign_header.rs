@@ -0,0 +1,42 @@
+//! Seals a header template with a local key and checks the signer, the way a remote signer would.
+//!
+//! ```sh
+//! cargo run --example sign_header
+//! ```
+
+use alloy_consensus::Header;
+use alloy_primitives::{Address, U256};
+use reth_bsc::consensus::{
+    recover_seal_signer, seal_hash, seal_header, sign_seal, verify_seal_signature, EXTRA_SEAL_LEN,
+    EXTRA_VANITY_LEN,
+};
+use secp256k1::{SecretKey, SECP256K1};
+
+fn main() {
+    let chain_id = 56;
+    let key = SecretKey::from_slice(&[0x42; 32]).expect("valid secret key");
+    let validator =
+        Address::from_raw_public_key(&key.public_key(SECP256K1).serialize_uncompressed()[1..]);
+
+    // template as handed out by the node: vanity followed by a zeroed seal placeholder
+    let mut header = Header {
+        beneficiary: validator,
+        difficulty: U256::from(2),
+        number: 1,
+        gas_limit: 140_000_000,
+        timestamp: 1_745_903_100,
+        extra_data: vec![0; EXTRA_VANITY_LEN + EXTRA_SEAL_LEN].into(),
+        ..Default::default()
+    };
+
+    let hash = seal_hash(&header, chain_id);
+    let seal = sign_seal(&header, chain_id, &key);
+    let signer = verify_seal_signature(&header, chain_id, &seal).expect("valid seal");
+    assert_eq!(signer, validator);
+
+    seal_header(&mut header, chain_id, &key).expect("extra-data has room for the seal");
+    assert_eq!(recover_seal_signer(&header, chain_id), Ok(validator));
+
+    println!("seal hash: {hash}");
+    println!("signer:    {signer}");
+}
@@ -0,0 +1,424 @@
+//! Public facade over the BSC system contracts and system transactions.
+//!
+//! Allows classifying BSC transactions without going through the block executor.
+use crate::hardforks::{bsc::BscHardfork, BscHardforks};
+use alloy_primitives::Address;
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolCall;
+
+pub use crate::system_contracts::{
+    is_system_transaction, CROSS_CHAIN_CONTRACT, GOVERNOR_CONTRACT, GOV_HUB_CONTRACT,
+    GOV_TOKEN_CONTRACT, LIGHT_CLIENT_CONTRACT, RELAYER_HUB_CONTRACT, RELAYER_INCENTIVIZE_CONTRACT,
+    SLASH_CONTRACT, STAKE_CREDIT_CONTRACT, STAKE_HUB_CONTRACT, STAKING_CONTRACT,
+    SYSTEM_REWARD_CONTRACT, TIMELOCK_CONTRACT, TOKEN_HUB_CONTRACT, TOKEN_MANAGER_CONTRACT,
+    TOKEN_RECOVER_PORTAL_CONTRACT, VALIDATOR_CONTRACT,
+};
+
+/// The BSC system contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BscSystemContract {
+    /// `BSCValidatorSet` contract.
+    ValidatorSet,
+    /// `SlashIndicator` contract.
+    SlashIndicator,
+    /// `SystemReward` contract.
+    SystemReward,
+    /// `TendermintLightClient` contract.
+    LightClient,
+    /// `TokenHub` contract.
+    TokenHub,
+    /// `RelayerIncentivize` contract.
+    RelayerIncentivize,
+    /// `RelayerHub` contract.
+    RelayerHub,
+    /// `GovHub` contract.
+    GovHub,
+    /// `TokenManager` contract.
+    TokenManager,
+    /// `CrossChain` contract.
+    CrossChain,
+    /// `Staking` contract.
+    Staking,
+    /// `StakeHub` contract.
+    StakeHub,
+    /// `StakeCredit` contract.
+    StakeCredit,
+    /// `BSCGovernor` contract.
+    Governor,
+    /// `GovToken` contract.
+    GovToken,
+    /// `BSCTimelock` contract.
+    Timelock,
+    /// `TokenRecoverPortal` contract.
+    TokenRecoverPortal,
+}
+
+impl BscSystemContract {
+    /// All system contracts.
+    pub const ALL: [Self; 17] = [
+        Self::ValidatorSet,
+        Self::SlashIndicator,
+        Self::SystemReward,
+        Self::LightClient,
+        Self::TokenHub,
+        Self::RelayerIncentivize,
+        Self::RelayerHub,
+        Self::GovHub,
+        Self::TokenManager,
+        Self::CrossChain,
+        Self::Staking,
+        Self::StakeHub,
+        Self::StakeCredit,
+        Self::Governor,
+        Self::GovToken,
+        Self::Timelock,
+        Self::TokenRecoverPortal,
+    ];
+
+    /// Returns the address of the system contract.
+    pub const fn address(&self) -> Address {
+        match self {
+            Self::ValidatorSet => VALIDATOR_CONTRACT,
+            Self::SlashIndicator => SLASH_CONTRACT,
+            Self::SystemReward => SYSTEM_REWARD_CONTRACT,
+            Self::LightClient => LIGHT_CLIENT_CONTRACT,
+            Self::TokenHub => TOKEN_HUB_CONTRACT,
+            Self::RelayerIncentivize => RELAYER_INCENTIVIZE_CONTRACT,
+            Self::RelayerHub => RELAYER_HUB_CONTRACT,
+            Self::GovHub => GOV_HUB_CONTRACT,
+            Self::TokenManager => TOKEN_MANAGER_CONTRACT,
+            Self::CrossChain => CROSS_CHAIN_CONTRACT,
+            Self::Staking => STAKING_CONTRACT,
+            Self::StakeHub => STAKE_HUB_CONTRACT,
+            Self::StakeCredit => STAKE_CREDIT_CONTRACT,
+            Self::Governor => GOVERNOR_CONTRACT,
+            Self::GovToken => GOV_TOKEN_CONTRACT,
+            Self::Timelock => TIMELOCK_CONTRACT,
+            Self::TokenRecoverPortal => TOKEN_RECOVER_PORTAL_CONTRACT,
+        }
+    }
+
+    /// Returns the system contract deployed at the given address, if any.
+    pub fn from_address(address: Address) -> Option<Self> {
+        Self::ALL.into_iter().find(|contract| contract.address() == address)
+    }
+
+    /// Returns the hardfork which deployed the contract, `None` if it is part of the genesis.
+    pub const fn introduced_in(&self) -> Option<BscHardfork> {
+        match self {
+            Self::ValidatorSet |
+            Self::SlashIndicator |
+            Self::SystemReward |
+            Self::LightClient |
+            Self::TokenHub |
+            Self::RelayerIncentivize |
+            Self::RelayerHub |
+            Self::GovHub |
+            Self::TokenManager |
+            Self::CrossChain => None,
+            Self::Staking => Some(BscHardfork::Gibbs),
+            Self::StakeHub |
+            Self::StakeCredit |
+            Self::Governor |
+            Self::GovToken |
+            Self::Timelock |
+            Self::TokenRecoverPortal => Some(BscHardfork::Feynman),
+        }
+    }
+
+    /// Returns true if the contract is deployed at the given block.
+    pub fn is_active_at<Spec: BscHardforks>(
+        &self,
+        spec: &Spec,
+        block_number: u64,
+        timestamp: u64,
+    ) -> bool {
+        self.introduced_in().is_none_or(|fork| {
+            spec.bsc_fork_activation(fork).active_at_timestamp_or_number(timestamp, block_number)
+        })
+    }
+}
+
+/// Returns true if the address is a system contract deployed at the given block.
+pub fn is_system_contract<Spec: BscHardforks>(
+    address: Address,
+    spec: &Spec,
+    block_number: u64,
+    timestamp: u64,
+) -> bool {
+    BscSystemContract::from_address(address)
+        .is_some_and(|contract| contract.is_active_at(spec, block_number, timestamp))
+}
+
+sol! {
+    #[sol(all_derives)]
+    function deposit(address valAddr);
+
+    #[sol(all_derives)]
+    function slash(address validator);
+
+    #[sol(all_derives)]
+    function distributeFinalityReward(address[] validators, uint256[] weights);
+
+    #[sol(all_derives)]
+    function updateValidatorSetV2(
+        address[] _consensusAddrs,
+        uint64[] _votingPowers,
+        bytes[] _voteAddrs
+    );
+}
+
+/// Kinds of the system calls found in BSC blocks, identified by their selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemCallKind {
+    /// Block reward deposit to the validator set contract.
+    Deposit,
+    /// Slashing of a validator which missed its turn.
+    Slash,
+    /// Distribution of the fast finality rewards.
+    DistributeFinalityReward,
+    /// Validator set update at the breathe block.
+    UpdateValidatorSetV2,
+}
+
+impl SystemCallKind {
+    /// Returns the kind of the call matching the selector of the calldata.
+    pub fn from_input(input: &[u8]) -> Option<Self> {
+        let selector: [u8; 4] = input.get(..4)?.try_into().ok()?;
+        match selector {
+            depositCall::SELECTOR => Some(Self::Deposit),
+            slashCall::SELECTOR => Some(Self::Slash),
+            distributeFinalityRewardCall::SELECTOR => Some(Self::DistributeFinalityReward),
+            updateValidatorSetV2Call::SELECTOR => Some(Self::UpdateValidatorSetV2),
+            _ => None,
+        }
+    }
+
+    /// Returns the selector of the call.
+    pub const fn selector(&self) -> [u8; 4] {
+        match self {
+            Self::Deposit => depositCall::SELECTOR,
+            Self::Slash => slashCall::SELECTOR,
+            Self::DistributeFinalityReward => distributeFinalityRewardCall::SELECTOR,
+            Self::UpdateValidatorSetV2 => updateValidatorSetV2Call::SELECTOR,
+        }
+    }
+}
+
+/// A decoded system call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemCall {
+    /// `deposit(address)`
+    Deposit(depositCall),
+    /// `slash(address)`
+    Slash(slashCall),
+    /// `distributeFinalityReward(address[],uint256[])`
+    DistributeFinalityReward(distributeFinalityRewardCall),
+    /// `updateValidatorSetV2(address[],uint64[],bytes[])`
+    UpdateValidatorSetV2(updateValidatorSetV2Call),
+}
+
+impl SystemCall {
+    /// Decodes the calldata of a system transaction, returns `None` for unknown or malformed
+    /// calls.
+    pub fn decode(input: &[u8]) -> Option<Self> {
+        match SystemCallKind::from_input(input)? {
+            SystemCallKind::Deposit => depositCall::abi_decode(input).ok().map(Self::Deposit),
+            SystemCallKind::Slash => slashCall::abi_decode(input).ok().map(Self::Slash),
+            SystemCallKind::DistributeFinalityReward => {
+                distributeFinalityRewardCall::abi_decode(input)
+                    .ok()
+                    .map(Self::DistributeFinalityReward)
+            }
+            SystemCallKind::UpdateValidatorSetV2 => {
+                updateValidatorSetV2Call::abi_decode(input).ok().map(Self::UpdateValidatorSetV2)
+            }
+        }
+    }
+
+    /// Returns the kind of the call.
+    pub const fn kind(&self) -> SystemCallKind {
+        match self {
+            Self::Deposit(_) => SystemCallKind::Deposit,
+            Self::Slash(_) => SystemCallKind::Slash,
+            Self::DistributeFinalityReward(_) => SystemCallKind::DistributeFinalityReward,
+            Self::UpdateValidatorSetV2(_) => SystemCallKind::UpdateValidatorSetV2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chainspec::{bsc::bsc_mainnet, BscChainSpec},
+        system_contracts::{
+            abi::{SLASH_INDICATOR_ABI, VALIDATOR_SET_ABI},
+            get_system_contract_codes,
+        },
+    };
+    use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+    use alloy_json_abi::JsonAbi;
+    use alloy_primitives::{address, hex, U256};
+
+    // validators of the mainnet genesis
+    const VALIDATOR_1: Address = address!("0x2a7cdd959bfe8d9487b2a43b33565295a698f7e2");
+    const VALIDATOR_2: Address = address!("0x6488aa4d1955ee33403f8ccb1d4de5fb97c7ade2");
+
+    /// Encodes a call with the ABI of the system contract, the way the node builds system
+    /// transactions.
+    fn contract_call(abi: &str, function: &str, args: &[DynSolValue]) -> Vec<u8> {
+        let abi: JsonAbi = serde_json::from_str(abi).unwrap();
+        abi.function(function).unwrap()[0].abi_encode_input(args).unwrap()
+    }
+
+    /// Returns true if the mainnet code of `contract` deployed by `hardfork` dispatches calls
+    /// with the given selector.
+    fn dispatches(hardfork: BscHardfork, contract: Address, selector: [u8; 4]) -> bool {
+        let codes = get_system_contract_codes(&bsc_mainnet(), hardfork.name()).unwrap();
+        let code = codes[&contract].as_ref().unwrap().original_bytes();
+        // the solidity dispatcher compares the selector of the call with `PUSH4 <selector>`
+        code.windows(5).any(|op| op[0] == 0x63 && op[1..] == selector)
+    }
+
+    #[test]
+    fn test_decode_deposit() {
+        let input =
+            hex!("f340fa010000000000000000000000002a7cdd959bfe8d9487b2a43b33565295a698f7e2");
+        assert_eq!(
+            input.as_slice(),
+            contract_call(*VALIDATOR_SET_ABI, "deposit", &[VALIDATOR_1.into()])
+        );
+        assert_eq!(SystemCallKind::from_input(&input), Some(SystemCallKind::Deposit));
+        assert_eq!(
+            SystemCall::decode(&input),
+            Some(SystemCall::Deposit(depositCall { valAddr: VALIDATOR_1 }))
+        );
+    }
+
+    #[test]
+    fn test_decode_slash() {
+        let input =
+            hex!("c96be4cb0000000000000000000000006488aa4d1955ee33403f8ccb1d4de5fb97c7ade2");
+        assert_eq!(
+            input.as_slice(),
+            contract_call(*SLASH_INDICATOR_ABI, "slash", &[VALIDATOR_2.into()])
+        );
+        assert_eq!(
+            SystemCall::decode(&input),
+            Some(SystemCall::Slash(slashCall { validator: VALIDATOR_2 }))
+        );
+
+        // a truncated call is still identified but can't be decoded
+        assert_eq!(SystemCallKind::from_input(&input[..20]), Some(SystemCallKind::Slash));
+        assert_eq!(SystemCall::decode(&input[..20]), None);
+    }
+
+    #[test]
+    fn test_decode_distribute_finality_reward() {
+        let input = hex!(
+            "300c3567"
+            "0000000000000000000000000000000000000000000000000000000000000040"
+            "00000000000000000000000000000000000000000000000000000000000000a0"
+            "0000000000000000000000000000000000000000000000000000000000000002"
+            "0000000000000000000000002a7cdd959bfe8d9487b2a43b33565295a698f7e2"
+            "0000000000000000000000006488aa4d1955ee33403f8ccb1d4de5fb97c7ade2"
+            "0000000000000000000000000000000000000000000000000000000000000002"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+            "0000000000000000000000000000000000000000000000000000000000000003"
+        );
+        let args = [
+            DynSolValue::Array(vec![VALIDATOR_1.into(), VALIDATOR_2.into()]),
+            DynSolValue::Array(vec![U256::from(1).into(), U256::from(3).into()]),
+        ];
+        assert_eq!(
+            input.as_slice(),
+            contract_call(*VALIDATOR_SET_ABI, "distributeFinalityReward", &args)
+        );
+        let Some(SystemCall::DistributeFinalityReward(call)) = SystemCall::decode(&input) else {
+            panic!("expected distributeFinalityReward call");
+        };
+        assert_eq!(call.validators, vec![VALIDATOR_1, VALIDATOR_2]);
+        assert_eq!(call.weights, vec![U256::from(1), U256::from(3)]);
+    }
+
+    #[test]
+    fn test_decode_update_validator_set_v2() {
+        let input = hex!(
+            "1e4c1524"
+            "0000000000000000000000000000000000000000000000000000000000000060"
+            "00000000000000000000000000000000000000000000000000000000000000a0"
+            "00000000000000000000000000000000000000000000000000000000000000e0"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+            "0000000000000000000000002a7cdd959bfe8d9487b2a43b33565295a698f7e2"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+            "00000000000000000000000000000000000000000000000000000000000003e8"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000030"
+            "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+            "2122232425262728292a2b2c2d2e2f3000000000000000000000000000000000"
+        );
+        let args = [
+            DynSolValue::Array(vec![VALIDATOR_1.into()]),
+            DynSolValue::Array(vec![DynSolValue::Uint(U256::from(1000), 64)]),
+            DynSolValue::Array(vec![DynSolValue::Bytes((1..=48).collect())]),
+        ];
+        assert_eq!(
+            input.as_slice(),
+            contract_call(*VALIDATOR_SET_ABI, "updateValidatorSetV2", &args)
+        );
+        let Some(SystemCall::UpdateValidatorSetV2(call)) = SystemCall::decode(&input) else {
+            panic!("expected updateValidatorSetV2 call");
+        };
+        assert_eq!(call._consensusAddrs, vec![VALIDATOR_1]);
+        assert_eq!(call._votingPowers, vec![1000]);
+        assert_eq!(call._voteAddrs.len(), 1);
+        assert_eq!(call._voteAddrs[0].as_ref(), (1..=48).collect::<Vec<u8>>().as_slice());
+    }
+
+    #[test]
+    fn test_selectors_match_mainnet_contracts() {
+        for (hardfork, contract, kind) in [
+            (BscHardfork::Lorentz, VALIDATOR_CONTRACT, SystemCallKind::Deposit),
+            (BscHardfork::Lorentz, VALIDATOR_CONTRACT, SystemCallKind::DistributeFinalityReward),
+            (BscHardfork::Lorentz, VALIDATOR_CONTRACT, SystemCallKind::UpdateValidatorSetV2),
+            (BscHardfork::Pascal, SLASH_CONTRACT, SystemCallKind::Slash),
+        ] {
+            assert!(dispatches(hardfork, contract, kind.selector()), "{kind:?} on {contract}");
+        }
+        let deposit = SystemCallKind::Deposit.selector();
+        assert!(!dispatches(BscHardfork::Pascal, SLASH_CONTRACT, deposit));
+    }
+
+    #[test]
+    fn test_unknown_selector() {
+        assert_eq!(SystemCallKind::from_input(&hex!("deadbeef")), None);
+        assert_eq!(SystemCallKind::from_input(&hex!("f340fa")), None);
+        assert_eq!(SystemCall::decode(&[]), None);
+    }
+
+    #[test]
+    fn test_registry() {
+        for contract in BscSystemContract::ALL {
+            assert_eq!(BscSystemContract::from_address(contract.address()), Some(contract));
+        }
+        assert_eq!(BscSystemContract::from_address(Address::ZERO), None);
+    }
+
+    #[test]
+    fn test_system_contract_availability() {
+        let spec = BscChainSpec::from(bsc_mainnet());
+
+        assert!(is_system_contract(VALIDATOR_CONTRACT, &spec, 1, 0));
+        assert!(!is_system_contract(Address::ZERO, &spec, 1, 0));
+
+        // Staking was deployed by Gibbs (block 23846001 on mainnet)
+        assert!(!is_system_contract(STAKING_CONTRACT, &spec, 23_846_000, 0));
+        assert!(is_system_contract(STAKING_CONTRACT, &spec, 23_846_001, 0));
+
+        // StakeHub was deployed by Feynman (1713419340 on mainnet)
+        assert!(!is_system_contract(STAKE_HUB_CONTRACT, &spec, 37_959_000, 1_713_419_339));
+        assert!(is_system_contract(STAKE_HUB_CONTRACT, &spec, 37_959_001, 1_713_419_340));
+    }
+}
@@ -1,15 +1,19 @@
 //! Chain specification for BSC, credits to: <https://github.com/bnb-chain/reth/blob/main/crates/bsc/chainspec/src/bsc.rs>
-use crate::hardforks::{bsc::BscHardfork, BscHardforks};
+use crate::{
+    bsc_system::BscSystemContract,
+    hardforks::{bsc::BscHardfork, BscHardforks},
+};
 use alloy_consensus::Header;
 use alloy_eips::eip7840::BlobParams;
 use alloy_genesis::Genesis;
 use alloy_primitives::{Address, B256, U256};
 use reth_chainspec::{
     BaseFeeParams, ChainSpec, DepositContract, EthChainSpec, EthereumHardfork, EthereumHardforks,
-    ForkCondition, ForkFilter, ForkId, Hardforks, Head, NamedChain,
+    ForkCondition, ForkFilter, ForkId, Hardfork, Hardforks, Head, NamedChain,
 };
 use reth_discv4::NodeRecord;
 use reth_evm::eth::spec::EthExecutorSpec;
+use revm::primitives::eip170::MAX_CODE_SIZE;
 use std::{fmt::Display, sync::Arc};
 
 pub mod bsc;
@@ -18,6 +22,27 @@ pub mod parser;
 
 pub use bsc_chapel::bsc_testnet;
 
+/// Errors returned by [`BscChainSpec::validate_hardfork_ordering`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HardforkOrderingError {
+    /// A hardfork activates before a hardfork it depends on.
+    #[error("hardfork {fork} activates before {dependency}")]
+    OutOfOrder {
+        /// The misconfigured hardfork.
+        fork: &'static str,
+        /// The hardfork it depends on.
+        dependency: &'static str,
+    },
+    /// Two hardforks that must activate together have different activations.
+    #[error("hardforks {fork} and {other} must activate together")]
+    NotSimultaneous {
+        /// The first hardfork.
+        fork: &'static str,
+        /// The hardfork it has to activate with.
+        other: &'static str,
+    },
+}
+
 /// Bsc chain spec type.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct BscChainSpec {
@@ -152,6 +177,192 @@ impl BscChainSpec {
             _ => bsc::head(),
         }
     }
+
+    /// Whether the given block is the genesis block.
+    pub const fn is_genesis_block(block_number: u64) -> bool {
+        block_number == 0
+    }
+
+    /// Whether the given block is the first block after genesis, which deploys the genesis
+    /// system contracts.
+    pub const fn is_first_block(block_number: u64) -> bool {
+        block_number == 1
+    }
+
+    /// Block from which EIP-155 replay protection is enforced, `eip155Block` of the genesis
+    /// config.
+    pub fn eip155_block(&self) -> u64 {
+        self.inner.genesis.config.eip155_block.unwrap_or_default()
+    }
+
+    /// Returns the address of the given system contract.
+    ///
+    /// The addresses are the same on all BSC networks, prefer this accessor over the raw
+    /// constants so call sites name the contract they mean.
+    pub const fn system_contract(&self, contract: BscSystemContract) -> Address {
+        contract.address()
+    }
+
+    /// Returns the address of the `Staking` contract.
+    pub const fn staking_contract_address(&self) -> Address {
+        self.system_contract(BscSystemContract::Staking)
+    }
+
+    /// Returns the address of the `CrossChain` contract.
+    pub const fn cross_chain_contract_address(&self) -> Address {
+        self.system_contract(BscSystemContract::CrossChain)
+    }
+
+    /// Maximum encoded size of a transaction accepted into the mempool.
+    ///
+    /// Matches `txMaxSize` of bsc-geth's legacy pool, i.e. 4 slots of 32 KB.
+    pub const fn max_tx_size_bytes(&self) -> usize {
+        4 * 32 * 1024
+    }
+
+    /// Maximum contract bytecode size at the given block.
+    ///
+    /// BSC follows the EIP-170 limit in all hardforks so far, forks changing it should be
+    /// matched here.
+    pub const fn max_code_size(&self, _block_number: u64, _timestamp: u64) -> usize {
+        MAX_CODE_SIZE
+    }
+
+    /// Upper bound of the header extra-data length at the given block, see
+    /// [`max_extra_data_size`](crate::consensus::max_extra_data_size).
+    pub fn max_extra_data_size(&self, block_number: u64, timestamp: u64) -> usize {
+        crate::consensus::max_extra_data_size(self, block_number, timestamp)
+    }
+
+    /// Target gas limit validators vote for when producing a block at the given block.
+    ///
+    /// Hertz set the target to 30M, Lorentz raised it to 70M and Maxwell to 140M. Earlier blocks
+    /// target the genesis gas limit.
+    pub fn preferred_gas_limit(&self, block_number: u64, timestamp: u64) -> u64 {
+        if self.is_maxwell_active_at_timestamp(timestamp) {
+            140_000_000
+        } else if self.is_lorentz_active_at_timestamp(timestamp) {
+            70_000_000
+        } else if self.is_hertz_active_at_block(block_number) {
+            30_000_000
+        } else {
+            self.inner.genesis.gas_limit
+        }
+    }
+
+    /// Gas limit of a block at the given block, voting from the parent gas limit towards the
+    /// [preferred gas limit](Self::preferred_gas_limit) at the maximum allowed rate.
+    pub fn next_gas_limit(&self, parent_gas_limit: u64, block_number: u64, timestamp: u64) -> u64 {
+        crate::consensus::calculate_gas_limit(
+            parent_gas_limit,
+            self.preferred_gas_limit(block_number, timestamp),
+        )
+    }
+
+    /// Ensures the configured hardforks don't contradict each other.
+    ///
+    /// Every hardfork must activate at or after the hardforks it depends on, and Shanghai/Kepler
+    /// as well as the Ethereum and BSC Cancun forks must activate together. Haber may follow
+    /// Cancun later, as it does on testnet. Hardforks that never activate only fail the check if
+    /// a hardfork depending on them is scheduled.
+    pub fn validate_hardfork_ordering(&self) -> Result<(), HardforkOrderingError> {
+        use BscHardfork as Bsc;
+        use EthereumHardfork as Eth;
+
+        let chains = [
+            // block based ethereum forks
+            vec![
+                self.named_fork(Eth::Frontier),
+                self.named_fork(Eth::Homestead),
+                self.named_fork(Eth::Tangerine),
+                self.named_fork(Eth::SpuriousDragon),
+                self.named_fork(Eth::Byzantium),
+                self.named_fork(Eth::Constantinople),
+                self.named_fork(Eth::Petersburg),
+                self.named_fork(Eth::Istanbul),
+                self.named_fork(Eth::MuirGlacier),
+                self.named_fork(Eth::Berlin),
+                self.named_fork(Eth::London),
+            ],
+            // block based bsc forks, Gibbs activates on either side of Nano and Moran
+            vec![
+                self.named_fork(Bsc::Ramanujan),
+                self.named_fork(Bsc::Niels),
+                self.named_fork(Bsc::MirrorSync),
+                self.named_fork(Bsc::Bruno),
+                self.named_fork(Bsc::Euler),
+                self.named_fork(Bsc::Nano),
+                self.named_fork(Bsc::Moran),
+                self.named_fork(Bsc::Planck),
+                self.named_fork(Bsc::Luban),
+                self.named_fork(Bsc::Plato),
+                self.named_fork(Bsc::Hertz),
+                self.named_fork(Bsc::HertzFix),
+            ],
+            vec![
+                self.named_fork(Bsc::Euler),
+                self.named_fork(Bsc::Gibbs),
+                self.named_fork(Bsc::Planck),
+            ],
+            // Hertz enables Berlin and London
+            vec![self.named_fork(Eth::London), self.named_fork(Bsc::Hertz)],
+            // timestamp based forks
+            vec![
+                self.named_fork(Eth::Shanghai),
+                self.named_fork(Bsc::Feynman),
+                self.named_fork(Bsc::FeynmanFix),
+                self.named_fork(Eth::Cancun),
+                self.named_fork(Bsc::Haber),
+                self.named_fork(Bsc::HaberFix),
+                self.named_fork(Bsc::Bohr),
+                self.named_fork(Eth::Prague),
+                self.named_fork(Bsc::Pascal),
+                self.named_fork(Bsc::Lorentz),
+                self.named_fork(Bsc::Maxwell),
+            ],
+        ];
+
+        for chain in &chains {
+            for pair in chain.windows(2) {
+                let ((dependency, dependency_condition), (fork, condition)) = (pair[0], pair[1]);
+                if activates_before(condition, dependency_condition) {
+                    return Err(HardforkOrderingError::OutOfOrder { fork, dependency });
+                }
+            }
+        }
+
+        let simultaneous = [
+            (self.named_fork(Eth::Shanghai), self.named_fork(Bsc::Kepler)),
+            (self.named_fork(Eth::Cancun), self.named_fork(Bsc::Cancun)),
+        ];
+        for ((fork, condition), (other, other_condition)) in simultaneous {
+            if condition != other_condition {
+                return Err(HardforkOrderingError::NotSimultaneous { fork, other });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the name and activation condition of the given hardfork.
+    fn named_fork<H: Hardfork>(&self, fork: H) -> (&'static str, ForkCondition) {
+        (fork.name(), self.inner.fork(fork))
+    }
+}
+
+/// Whether `fork` activates before `dependency`.
+///
+/// A fork that never activates never comes first, a scheduled fork always comes before a
+/// dependency that never activates, and forks scheduled by different kinds of conditions can't be
+/// ordered.
+fn activates_before(fork: ForkCondition, dependency: ForkCondition) -> bool {
+    match (fork, dependency) {
+        (ForkCondition::Never, _) => false,
+        (_, ForkCondition::Never) => true,
+        (ForkCondition::Block(fork), ForkCondition::Block(dependency)) |
+        (ForkCondition::Timestamp(fork), ForkCondition::Timestamp(dependency)) => fork < dependency,
+        _ => true,
+    }
 }
 
 impl From<BscChainSpec> for ChainSpec {
@@ -169,7 +380,205 @@ impl BscHardforks for Arc<BscChainSpec> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::chainspec::bsc_chapel::bsc_testnet;
+    use crate::{
+        chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet},
+        consensus::{
+            EXTRA_SEAL_LEN, EXTRA_VANITY_LEN, MAX_ATTESTATION_LEN, TURN_LENGTH_SIZE,
+            VALIDATOR_BYTES_LEN_AFTER_LUBAN, VALIDATOR_BYTES_LEN_BEFORE_LUBAN,
+            VALIDATOR_NUMBER_SIZE,
+        },
+    };
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_system_contract_addresses() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+
+        let expected = [
+            (
+                BscSystemContract::ValidatorSet,
+                address!("0x0000000000000000000000000000000000001000"),
+            ),
+            (
+                BscSystemContract::SlashIndicator,
+                address!("0x0000000000000000000000000000000000001001"),
+            ),
+            (
+                BscSystemContract::SystemReward,
+                address!("0x0000000000000000000000000000000000001002"),
+            ),
+            (
+                BscSystemContract::LightClient,
+                address!("0x0000000000000000000000000000000000001003"),
+            ),
+            (BscSystemContract::TokenHub, address!("0x0000000000000000000000000000000000001004")),
+            (
+                BscSystemContract::RelayerIncentivize,
+                address!("0x0000000000000000000000000000000000001005"),
+            ),
+            (BscSystemContract::RelayerHub, address!("0x0000000000000000000000000000000000001006")),
+            (BscSystemContract::GovHub, address!("0x0000000000000000000000000000000000001007")),
+            (
+                BscSystemContract::TokenManager,
+                address!("0x0000000000000000000000000000000000001008"),
+            ),
+            (BscSystemContract::CrossChain, address!("0x0000000000000000000000000000000000002000")),
+            (BscSystemContract::Staking, address!("0x0000000000000000000000000000000000002001")),
+            (BscSystemContract::StakeHub, address!("0x0000000000000000000000000000000000002002")),
+            (
+                BscSystemContract::StakeCredit,
+                address!("0x0000000000000000000000000000000000002003"),
+            ),
+            (BscSystemContract::Governor, address!("0x0000000000000000000000000000000000002004")),
+            (BscSystemContract::GovToken, address!("0x0000000000000000000000000000000000002005")),
+            (BscSystemContract::Timelock, address!("0x0000000000000000000000000000000000002006")),
+            (
+                BscSystemContract::TokenRecoverPortal,
+                address!("0x0000000000000000000000000000000000003000"),
+            ),
+        ];
+        assert_eq!(expected.len(), BscSystemContract::ALL.len());
+
+        for (contract, address) in expected {
+            assert_eq!(chain_spec.system_contract(contract), address);
+
+            // contracts present since genesis are deployed by the genesis alloc
+            let alloc = chain_spec.inner.genesis.alloc.get(&address);
+            if contract.introduced_in().is_none() {
+                assert!(alloc.and_then(|account| account.code.as_ref()).is_some());
+            } else {
+                assert!(alloc.is_none());
+            }
+        }
+
+        assert_eq!(chain_spec.staking_contract_address(), expected[10].1);
+        assert_eq!(chain_spec.cross_chain_contract_address(), expected[9].1);
+    }
+
+    #[test]
+    fn test_genesis_and_first_block() {
+        assert!(BscChainSpec::is_genesis_block(0));
+        assert!(!BscChainSpec::is_genesis_block(1));
+        assert!(!BscChainSpec::is_genesis_block(2));
+
+        assert!(!BscChainSpec::is_first_block(0));
+        assert!(BscChainSpec::is_first_block(1));
+        assert!(!BscChainSpec::is_first_block(2));
+    }
+
+    #[test]
+    fn test_max_code_size() {
+        for chain_spec in [BscChainSpec::from(bsc_mainnet()), BscChainSpec::from(bsc_testnet())] {
+            let head = chain_spec.head();
+            assert_eq!(chain_spec.max_code_size(head.number, head.timestamp), 24_576);
+            assert_eq!(chain_spec.max_code_size(0, 0), 24_576);
+        }
+    }
+
+    #[test]
+    fn test_max_extra_data_size() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+
+        // pre-Luban non-epoch blocks only carry vanity and seal
+        assert_eq!(chain_spec.max_extra_data_size(1_000_001, 0), 97);
+
+        // pre-Luban epoch block with 21 validators
+        let len = EXTRA_VANITY_LEN + 21 * VALIDATOR_BYTES_LEN_BEFORE_LUBAN + EXTRA_SEAL_LEN;
+        assert!(len <= chain_spec.max_extra_data_size(1_000_000, 0));
+        assert!(len > chain_spec.max_extra_data_size(1_000_001, 0));
+
+        // post-Bohr epoch block with 45 validators, turn length and attestation
+        let (number, timestamp) = (43_000_000, 1_727_317_200);
+        let len = EXTRA_VANITY_LEN +
+            VALIDATOR_NUMBER_SIZE +
+            45 * VALIDATOR_BYTES_LEN_AFTER_LUBAN +
+            TURN_LENGTH_SIZE +
+            MAX_ATTESTATION_LEN +
+            EXTRA_SEAL_LEN;
+        assert!(len <= chain_spec.max_extra_data_size(number, timestamp));
+        assert!(len > chain_spec.max_extra_data_size(number + 1, timestamp));
+        assert_eq!(
+            chain_spec.max_extra_data_size(number + 1, timestamp),
+            EXTRA_VANITY_LEN + MAX_ATTESTATION_LEN + EXTRA_SEAL_LEN
+        );
+
+        // since Lorentz, multiples of 500 may be epoch blocks too
+        let lorentz = 1_745_903_100;
+        assert!(len > chain_spec.max_extra_data_size(48_000_500, lorentz - 1));
+        assert!(len <= chain_spec.max_extra_data_size(48_000_500, lorentz));
+    }
+
+    #[test]
+    fn test_preferred_gas_limit() {
+        let mainnet = BscChainSpec::from(bsc_mainnet());
+        assert_eq!(mainnet.preferred_gas_limit(0, 0), 40_000_000);
+        assert_eq!(mainnet.preferred_gas_limit(31_302_047, 0), 40_000_000);
+        assert_eq!(mainnet.preferred_gas_limit(31_302_048, 0), 30_000_000);
+        assert_eq!(mainnet.preferred_gas_limit(34_140_700, 1_713_419_340), 30_000_000);
+
+        let testnet = BscChainSpec::from(bsc_testnet());
+        assert_eq!(testnet.preferred_gas_limit(31_103_029, 0), 40_000_000);
+        assert_eq!(testnet.preferred_gas_limit(31_103_030, 0), 30_000_000);
+
+        assert_eq!(mainnet.preferred_gas_limit(48_000_000, 1_745_903_099), 30_000_000);
+        assert_eq!(mainnet.preferred_gas_limit(48_000_000, 1_745_903_100), 70_000_000);
+        assert_eq!(mainnet.preferred_gas_limit(50_000_000, 1_751_250_600), 140_000_000);
+    }
+
+    #[test]
+    fn test_next_gas_limit() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+
+        // (parent gas limit, timestamp, target, blocks to reach the target)
+        let cases = [
+            (30_000_000, 1_745_903_100, 70_000_000, 218),
+            (70_000_000, 1_751_250_600, 140_000_000, 178),
+            (140_000_000, 1_745_903_100, 70_000_000, 178),
+        ];
+        for (mut gas_limit, timestamp, target, blocks) in cases {
+            for _ in 0..blocks {
+                let next = chain_spec.next_gas_limit(gas_limit, 50_000_000, timestamp);
+                let max_delta = gas_limit / 256 - 1;
+                assert!(next.abs_diff(gas_limit) == max_delta || next == target);
+                gas_limit = next;
+            }
+            assert_eq!(gas_limit, target);
+            assert_eq!(chain_spec.next_gas_limit(gas_limit, 50_000_000, timestamp), target);
+        }
+    }
+
+    #[test]
+    fn test_validate_hardfork_ordering() {
+        assert_eq!(BscChainSpec::from(bsc_mainnet()).validate_hardfork_ordering(), Ok(()));
+        assert_eq!(BscChainSpec::from(bsc_testnet()).validate_hardfork_ordering(), Ok(()));
+
+        // Hertz scheduled before London
+        let mut chain_spec = BscChainSpec::from(bsc_mainnet());
+        let hardforks = &mut chain_spec.inner.hardforks;
+        hardforks.insert(EthereumHardfork::Berlin, ForkCondition::Block(40_000_000));
+        hardforks.insert(EthereumHardfork::London, ForkCondition::Block(40_000_000));
+        assert_eq!(
+            chain_spec.validate_hardfork_ordering(),
+            Err(HardforkOrderingError::OutOfOrder { fork: "Hertz", dependency: "London" })
+        );
+
+        // Bohr scheduled while its dependency never activates
+        let mut chain_spec = BscChainSpec::from(bsc_mainnet());
+        chain_spec.inner.hardforks.insert(BscHardfork::HaberFix, ForkCondition::Never);
+        assert_eq!(
+            chain_spec.validate_hardfork_ordering(),
+            Err(HardforkOrderingError::OutOfOrder { fork: "Bohr", dependency: "HaberFix" })
+        );
+
+        // Kepler activating apart from Shanghai
+        let mut chain_spec = BscChainSpec::from(bsc_mainnet());
+        let kepler = ForkCondition::Timestamp(1_705_996_801);
+        chain_spec.inner.hardforks.insert(BscHardfork::Kepler, kepler);
+        assert_eq!(
+            chain_spec.validate_hardfork_ordering(),
+            Err(HardforkOrderingError::NotSimultaneous { fork: "Shanghai", other: "Kepler" })
+        );
+    }
 
     #[test]
     fn test_blob_params_at_timestamp() {
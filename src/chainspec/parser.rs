@@ -22,9 +22,11 @@ impl ChainSpecParser for BscChainSpecParser {
 /// The value parser matches either a known chain, the path
 /// to a json file, or a json formatted string in-memory. The json needs to be a Genesis struct.
 pub fn chain_value_parser(s: &str) -> eyre::Result<Arc<BscChainSpec>> {
-    match s {
-        "bsc" => Ok(Arc::new(BscChainSpec { inner: bsc_mainnet() })),
-        "bsc-testnet" => Ok(Arc::new(BscChainSpec { inner: bsc_testnet() })),
-        _ => Err(eyre::eyre!("Unsupported chain: {}", s)),
-    }
+    let chain_spec = match s {
+        "bsc" => BscChainSpec { inner: bsc_mainnet() },
+        "bsc-testnet" => BscChainSpec { inner: bsc_testnet() },
+        _ => return Err(eyre::eyre!("Unsupported chain: {}", s)),
+    };
+    chain_spec.validate_hardfork_ordering()?;
+    Ok(Arc::new(chain_spec))
 }
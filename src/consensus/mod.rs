@@ -1,14 +1,231 @@
-use alloy_consensus::constants::ETH_TO_WEI;
-use alloy_primitives::{address, Address, BlockNumber, B256};
-use reth_provider::{BlockNumReader, ProviderError};
+use crate::hardforks::BscHardforks;
+use alloy_consensus::{constants::ETH_TO_WEI, Header};
+use alloy_primitives::{address, Address, BlockNumber, B256, U256};
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderError};
 use std::cmp::Ordering;
 
+mod performance;
+mod proposers;
+mod rotation;
+mod seal;
+pub use performance::{ValidatorPerfTracker, ValidatorPerformance, DEFAULT_PERFORMANCE_WINDOWS};
+pub use proposers::{ProposerArchive, ProposerRecord};
+#[cfg(test)]
+pub(crate) use rotation::tests::epoch_header;
+pub use rotation::{
+    epoch_length_at, next_epoch_length, rotation_at, Rotation, ValidatorInfo, VoteAddress,
+};
+pub use seal::{
+    recover_seal_signer, seal_hash, seal_header, sign_seal, verify_seal_signature, SealError,
+};
+
 pub const SYSTEM_ADDRESS: Address = address!("0xfffffffffffffffffffffffffffffffffffffffe");
 /// The reward percent to system
 pub const SYSTEM_REWARD_PERCENT: usize = 4;
 /// The max reward in system reward contract
 pub const MAX_SYSTEM_REWARD: u128 = 100 * ETH_TO_WEI;
 
+/// Difficulty of a block sealed by the in-turn validator
+pub const DIFF_INTURN: U256 = U256::from_limbs([2, 0, 0, 0]);
+/// Difficulty of a block sealed by an out-of-turn validator
+pub const DIFF_NOTURN: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// Fixed number of extra-data prefix bytes reserved for signer vanity
+pub const EXTRA_VANITY_LEN: usize = 32;
+/// Fixed number of extra-data suffix bytes reserved for signer seal
+pub const EXTRA_SEAL_LEN: usize = 65;
+/// Size of a validator entry in the epoch extra-data before Luban
+pub const VALIDATOR_BYTES_LEN_BEFORE_LUBAN: usize = 20;
+/// Size of a BLS public key
+pub const BLS_PUBLIC_KEY_LEN: usize = 48;
+/// Size of a validator entry (address + BLS public key) in the epoch extra-data since Luban
+pub const VALIDATOR_BYTES_LEN_AFTER_LUBAN: usize =
+    VALIDATOR_BYTES_LEN_BEFORE_LUBAN + BLS_PUBLIC_KEY_LEN;
+/// Size of the validator count prefix in the epoch extra-data since Luban
+pub const VALIDATOR_NUMBER_SIZE: usize = 1;
+/// Size of the turn length in the epoch extra-data since Bohr
+pub const TURN_LENGTH_SIZE: usize = 1;
+/// Smallest turn length accepted by the validator set contract, a single block per validator
+pub const MIN_TURN_LENGTH: u8 = 1;
+/// Smallest turn length accepted by the validator set contract above [`MIN_TURN_LENGTH`]
+pub const MIN_MULTI_BLOCK_TURN_LENGTH: u8 = 3;
+/// Largest turn length accepted by the validator set contract
+pub const MAX_TURN_LENGTH: u8 = 64;
+/// Upper bound of the validators in an epoch extra-data, the count is a single byte since Luban
+pub const MAX_VALIDATORS: usize = u8::MAX as usize;
+/// Size of an aggregated BLS signature
+pub const BLS_SIGNATURE_LEN: usize = 96;
+/// Upper bound of the `extra` field of a vote attestation
+pub const MAX_ATTESTATION_EXTRA_LEN: usize = 256;
+/// Upper bound of the RLP encoded vote attestation:
+/// list header, vote address set, aggregated signature, vote data and extra.
+pub const MAX_ATTESTATION_LEN: usize =
+    3 + 9 + (2 + BLS_SIGNATURE_LEN) + (2 + 2 * (9 + 33)) + (3 + MAX_ATTESTATION_EXTRA_LEN);
+
+// The extra-data layout is shared with bsc-geth, keep the derived sizes in line with it.
+const _: () = {
+    assert!(EXTRA_VANITY_LEN == size_of::<B256>());
+    assert!(EXTRA_SEAL_LEN == 2 * size_of::<B256>() + 1);
+    assert!(VALIDATOR_BYTES_LEN_BEFORE_LUBAN == size_of::<Address>());
+    assert!(VALIDATOR_BYTES_LEN_AFTER_LUBAN == 68);
+    assert!(MAX_VALIDATORS < 1 << (8 * VALIDATOR_NUMBER_SIZE));
+};
+
+/// Number of blocks per epoch until Lorentz
+pub const DEFAULT_EPOCH_LENGTH: u64 = 200;
+/// Number of blocks per epoch since Lorentz
+pub const LORENTZ_EPOCH_LENGTH: u64 = 500;
+/// Number of blocks per epoch since Maxwell
+pub const MAXWELL_EPOCH_LENGTH: u64 = 1000;
+
+/// Returns the number of blocks per epoch of the hardforks active at the given timestamp.
+pub fn epoch_length<ChainSpec: BscHardforks>(chain_spec: &ChainSpec, timestamp: u64) -> u64 {
+    if chain_spec.is_maxwell_active_at_timestamp(timestamp) {
+        MAXWELL_EPOCH_LENGTH
+    } else if chain_spec.is_lorentz_active_at_timestamp(timestamp) {
+        LORENTZ_EPOCH_LENGTH
+    } else {
+        DEFAULT_EPOCH_LENGTH
+    }
+}
+
+/// Interval between blocks until Lorentz, in milliseconds
+pub const DEFAULT_BLOCK_INTERVAL_MS: u64 = 3_000;
+/// Interval between blocks since Lorentz, in milliseconds
+pub const LORENTZ_BLOCK_INTERVAL_MS: u64 = 1_500;
+/// Interval between blocks since Maxwell, in milliseconds
+pub const MAXWELL_BLOCK_INTERVAL_MS: u64 = 750;
+
+/// Returns the interval between blocks, in milliseconds, of the hardforks active at the given
+/// timestamp.
+pub fn block_interval_ms<ChainSpec: BscHardforks>(chain_spec: &ChainSpec, timestamp: u64) -> u64 {
+    if chain_spec.is_maxwell_active_at_timestamp(timestamp) {
+        MAXWELL_BLOCK_INTERVAL_MS
+    } else if chain_spec.is_lorentz_active_at_timestamp(timestamp) {
+        LORENTZ_BLOCK_INTERVAL_MS
+    } else {
+        DEFAULT_BLOCK_INTERVAL_MS
+    }
+}
+
+/// Returns the upper bound of the extra-data length of a header at the given block.
+///
+/// Every header carries vanity and seal, an attestation may follow since Luban and epoch headers
+/// add the full validator set and, since Bohr, the turn length. Epoch length grows from Lorentz
+/// on, so the bound treats a block as an epoch block under any of the applicable lengths.
+pub fn max_extra_data_size<ChainSpec: BscHardforks>(
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    timestamp: u64,
+) -> usize {
+    let mut max = EXTRA_VANITY_LEN + EXTRA_SEAL_LEN;
+    let luban = chain_spec.is_luban_active_at_block(block_number);
+
+    if luban {
+        max += MAX_ATTESTATION_LEN;
+    }
+
+    let is_epoch = block_number % DEFAULT_EPOCH_LENGTH == 0 ||
+        (chain_spec.is_lorentz_active_at_timestamp(timestamp) &&
+            block_number % LORENTZ_EPOCH_LENGTH == 0);
+    if is_epoch {
+        max += if luban {
+            VALIDATOR_NUMBER_SIZE + MAX_VALIDATORS * VALIDATOR_BYTES_LEN_AFTER_LUBAN
+        } else {
+            MAX_VALIDATORS * VALIDATOR_BYTES_LEN_BEFORE_LUBAN
+        };
+
+        if chain_spec.is_bohr_active_at_timestamp(timestamp) {
+            max += TURN_LENGTH_SIZE;
+        }
+    }
+
+    max
+}
+
+/// Reads the turn length from the extra-data of an epoch header since Bohr.
+///
+/// The turn length follows the validator set:
+/// `vanity | validator count | validators | turn length | attestation | seal`. Whether a header is
+/// an epoch header depends on the epoch length of the validator snapshot, so the caller has to
+/// ensure it. Turn lengths not accepted by the validator set contract are rejected, see
+/// [`is_valid_turn_length`], a block producer could otherwise stretch its turn arbitrarily.
+pub fn parse_turn_length(extra_data: &[u8]) -> Result<u8, ParliaConsensusErr> {
+    let validators_end = extra_data.get(EXTRA_VANITY_LEN).map(|&count| {
+        EXTRA_VANITY_LEN + VALIDATOR_NUMBER_SIZE + count as usize * VALIDATOR_BYTES_LEN_AFTER_LUBAN
+    });
+    let turn_length = validators_end
+        .filter(|&pos| pos + TURN_LENGTH_SIZE + EXTRA_SEAL_LEN <= extra_data.len())
+        .map(|pos| extra_data[pos])
+        .ok_or(ParliaConsensusErr::MissingTurnLength)?;
+
+    if !is_valid_turn_length(turn_length) {
+        return Err(ParliaConsensusErr::InvalidTurnLength(turn_length))
+    }
+    Ok(turn_length)
+}
+
+/// Reads the validator set from the extra-data of an epoch header since Luban.
+///
+/// Returns the consensus addresses along with the BLS vote addresses following each of them, in
+/// the order of the extra-data. As for [`parse_turn_length`], the caller has to ensure the header
+/// is an epoch header.
+pub fn parse_validators(extra_data: &[u8]) -> Result<Vec<ValidatorInfo>, ParliaConsensusErr> {
+    let count = extra_data.get(EXTRA_VANITY_LEN).map_or(0, |&count| count as usize);
+    let start = EXTRA_VANITY_LEN + VALIDATOR_NUMBER_SIZE;
+    let end = start + count * VALIDATOR_BYTES_LEN_AFTER_LUBAN;
+    if count == 0 || end + EXTRA_SEAL_LEN > extra_data.len() {
+        return Err(ParliaConsensusErr::MissingValidators)
+    }
+    Ok(extra_data[start..end]
+        .chunks_exact(VALIDATOR_BYTES_LEN_AFTER_LUBAN)
+        .map(|validator| {
+            let (address, vote_address) = validator.split_at(VALIDATOR_BYTES_LEN_BEFORE_LUBAN);
+            ValidatorInfo {
+                address: Address::from_slice(address),
+                vote_address: VoteAddress::from_slice(vote_address),
+            }
+        })
+        .collect())
+}
+
+/// Returns whether the validator set contract accepts the turn length, either a single block or
+/// [`MIN_MULTI_BLOCK_TURN_LENGTH`]`..=`[`MAX_TURN_LENGTH`] consecutive blocks.
+pub const fn is_valid_turn_length(turn_length: u8) -> bool {
+    matches!(turn_length, MIN_TURN_LENGTH | MIN_MULTI_BLOCK_TURN_LENGTH..=MAX_TURN_LENGTH)
+}
+
+/// Bound divisor of the gas limit, a block moves the gas limit by less than `parent / 256`
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 256;
+/// Minimum gas limit of a block
+pub const MIN_GAS_LIMIT: u64 = 5_000;
+
+/// Returns the gas limit of a block voting from the parent gas limit towards `desired_limit`.
+///
+/// Mirrors `CalcGasLimit` of bsc-geth, the limit moves by at most `parent / 256 - 1` per block.
+pub const fn calculate_gas_limit(parent_gas_limit: u64, desired_limit: u64) -> u64 {
+    let delta = (parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+    let desired_limit = if desired_limit < MIN_GAS_LIMIT { MIN_GAS_LIMIT } else { desired_limit };
+
+    if parent_gas_limit < desired_limit {
+        let limit = parent_gas_limit + delta;
+        if limit > desired_limit {
+            desired_limit
+        } else {
+            limit
+        }
+    } else if parent_gas_limit > desired_limit {
+        let limit = parent_gas_limit - delta;
+        if limit < desired_limit {
+            desired_limit
+        } else {
+            limit
+        }
+    } else {
+        parent_gas_limit
+    }
+}
+
 /// Errors that can occur in Parlia consensus
 #[derive(Debug, thiserror::Error)]
 pub enum ParliaConsensusErr {
@@ -18,6 +235,21 @@ pub enum ParliaConsensusErr {
     /// Head block hash not found
     #[error("Head block hash not found")]
     HeadHashNotFound,
+    /// Header of a canonical block not found
+    #[error("Header {0} not found")]
+    HeaderNotFound(BlockNumber),
+    /// The epoch header extra-data ends before the validator set
+    #[error("Validator set missing from epoch header")]
+    MissingValidators,
+    /// The epoch header lists its validators without vote addresses, as before Luban
+    #[error("Validator set of block {0} predates Luban")]
+    PreLubanValidators(BlockNumber),
+    /// The epoch header extra-data ends before the turn length
+    #[error("Turn length missing from epoch header")]
+    MissingTurnLength,
+    /// The epoch header carries a turn length outside of the allowed range
+    #[error("Turn length {0} out of range")]
+    InvalidTurnLength(u8),
 }
 
 /// Parlia consensus implementation
@@ -28,106 +260,165 @@ pub struct ParliaConsensus<P> {
 
 impl<P> ParliaConsensus<P>
 where
-    P: BlockNumReader + Clone,
+    P: BlockNumReader + HeaderProvider<Header = Header> + Clone,
 {
+    /// Create a new instance of [`ParliaConsensus`]
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+
     /// Determines the head block hash according to Parlia consensus rules:
     /// 1. Follow the highest block number
-    /// 2. For same height blocks, pick the one with lower hash
+    /// 2. For same height blocks, pick the one with higher difficulty, an in-turn block outweighs
+    ///    an out-of-turn one
+    /// 3. For same height and difficulty, pick the one with lower hash
+    ///
+    /// The difficulty is the one of the sealed header, the total difficulty a peer announces
+    /// along with the block is not trusted.
     pub(crate) fn canonical_head(
         &self,
         hash: B256,
         number: BlockNumber,
+        difficulty: U256,
     ) -> Result<(B256, B256), ParliaConsensusErr> {
         let current_head = self.provider.best_block_number()?;
-        let current_hash =
-            self.provider.block_hash(current_head)?.ok_or(ParliaConsensusErr::HeadHashNotFound)?;
+        let current = self
+            .provider
+            .sealed_header(current_head)?
+            .ok_or(ParliaConsensusErr::HeadHashNotFound)?;
+        let current_hash = current.hash();
 
-        match number.cmp(&current_head) {
-            Ordering::Greater => Ok((hash, current_hash)),
-            Ordering::Equal => Ok((hash.min(current_hash), current_hash)),
-            Ordering::Less => Ok((current_hash, current_hash)),
-        }
+        let head = match number.cmp(&current_head) {
+            Ordering::Greater => hash,
+            Ordering::Equal => match difficulty.cmp(&current.difficulty) {
+                Ordering::Greater => hash,
+                Ordering::Less => current_hash,
+                Ordering::Equal => hash.min(current_hash),
+            },
+            Ordering::Less => current_hash,
+        };
+
+        Ok((head, current_hash))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_primitives::hex;
-    use reth_chainspec::ChainInfo;
-    use reth_provider::BlockHashReader;
-    use std::collections::HashMap;
-
-    #[derive(Clone)]
-    struct MockProvider {
-        blocks: HashMap<BlockNumber, B256>,
-        head_number: BlockNumber,
-        head_hash: B256,
+    use reth_provider::test_utils::MockEthProvider;
+
+    /// Returns the head chosen against the canonical chain ending at `head`, checking the current
+    /// head hash along the way.
+    fn canonical_head(head: &Header, block: &Header) -> B256 {
+        let provider = MockEthProvider::default();
+        provider.add_header(head.hash_slow(), head.clone());
+        let consensus = ParliaConsensus::new(provider);
+        let (head_block_hash, current_hash) =
+            consensus.canonical_head(block.hash_slow(), block.number, block.difficulty).unwrap();
+        assert_eq!(current_hash, head.hash_slow());
+        head_block_hash
     }
 
-    impl MockProvider {
-        fn new(head_number: BlockNumber, head_hash: B256) -> Self {
-            let mut blocks = HashMap::new();
-            blocks.insert(head_number, head_hash);
-            Self { blocks, head_number, head_hash }
-        }
+    fn header(number: BlockNumber, difficulty: U256, salt: u8) -> Header {
+        Header { number, difficulty, mix_hash: B256::with_last_byte(salt), ..Default::default() }
     }
 
-    impl BlockHashReader for MockProvider {
-        fn block_hash(&self, number: BlockNumber) -> Result<Option<B256>, ProviderError> {
-            Ok(self.blocks.get(&number).copied())
-        }
+    #[test]
+    fn test_canonical_head() {
+        let (first, second) = (header(1, DIFF_INTURN, 1), header(1, DIFF_INTURN, 2));
+        let (lower, higher) =
+            if first.hash_slow() < second.hash_slow() { (first, second) } else { (second, first) };
+        let above = header(2, DIFF_INTURN, 0);
 
-        fn canonical_hashes_range(
-            &self,
-            _start: BlockNumber,
-            _end: BlockNumber,
-        ) -> Result<Vec<B256>, ProviderError> {
-            Ok(vec![])
-        }
-    }
+        let test_cases = [
+            ((&above, &lower), &above),  // Higher block wins
+            ((&lower, &above), &above),  // Lower block stays
+            ((&lower, &higher), &lower), // Same height, lower hash wins
+            ((&higher, &lower), &lower), // Same height, lower hash stays
+        ];
 
-    impl BlockNumReader for MockProvider {
-        fn chain_info(&self) -> Result<ChainInfo, ProviderError> {
-            Ok(ChainInfo { best_hash: self.head_hash, best_number: self.head_number })
+        for ((block, head), expected) in test_cases {
+            assert_eq!(canonical_head(head, block), expected.hash_slow());
         }
+    }
 
-        fn best_block_number(&self) -> Result<BlockNumber, ProviderError> {
-            Ok(self.head_number)
-        }
+    #[test]
+    fn test_canonical_head_by_difficulty() {
+        let in_turn = header(10, DIFF_INTURN, 0);
+        // a lower hash would win the tie-break between blocks of the same difficulty
+        let out_of_turn = (1..=u8::MAX)
+            .map(|salt| header(10, DIFF_NOTURN, salt))
+            .find(|header| header.hash_slow() < in_turn.hash_slow())
+            .unwrap();
 
-        fn last_block_number(&self) -> Result<BlockNumber, ProviderError> {
-            Ok(self.head_number)
-        }
+        // the difficulty of the current head is read from its sealed header
+        assert_eq!(canonical_head(&in_turn, &out_of_turn), in_turn.hash_slow());
+        assert_eq!(canonical_head(&out_of_turn, &in_turn), in_turn.hash_slow());
 
-        fn block_number(&self, hash: B256) -> Result<Option<BlockNumber>, ProviderError> {
-            Ok(self.blocks.iter().find_map(|(num, h)| (*h == hash).then_some(*num)))
-        }
+        // same difficulty falls back to the lower hash
+        let other = header(10, DIFF_INTURN, 1);
+        let lower = in_turn.hash_slow().min(other.hash_slow());
+        assert_eq!(canonical_head(&in_turn, &other), lower);
+        assert_eq!(canonical_head(&other, &in_turn), lower);
     }
 
     #[test]
-    fn test_canonical_head() {
-        let hash1 = B256::from_slice(&hex!(
-            "1111111111111111111111111111111111111111111111111111111111111111"
+    fn test_parse_turn_length() {
+        let extra = |validators: u8, turn_length: Option<u8>| {
+            let mut extra = vec![0u8; EXTRA_VANITY_LEN];
+            extra.push(validators);
+            extra.resize(extra.len() + validators as usize * VALIDATOR_BYTES_LEN_AFTER_LUBAN, 0xab);
+            extra.extend(turn_length);
+            extra.extend_from_slice(&[0u8; EXTRA_SEAL_LEN]);
+            extra
+        };
+
+        assert_eq!(parse_turn_length(&extra(21, Some(1))).unwrap(), 1);
+        assert_eq!(parse_turn_length(&extra(21, Some(3))).unwrap(), 3);
+        assert_eq!(parse_turn_length(&extra(45, Some(16))).unwrap(), 16);
+        assert_eq!(parse_turn_length(&extra(45, Some(MAX_TURN_LENGTH))).unwrap(), MAX_TURN_LENGTH);
+        assert!(matches!(
+            parse_turn_length(&extra(21, Some(255))),
+            Err(ParliaConsensusErr::InvalidTurnLength(255))
+        ));
+        assert!(matches!(
+            parse_turn_length(&extra(21, Some(0))),
+            Err(ParliaConsensusErr::InvalidTurnLength(0))
+        ));
+        // two blocks per validator aren't accepted by the contract
+        assert!(matches!(
+            parse_turn_length(&extra(21, Some(2))),
+            Err(ParliaConsensusErr::InvalidTurnLength(2))
         ));
-        let hash2 = B256::from_slice(&hex!(
-            "2222222222222222222222222222222222222222222222222222222222222222"
+        assert!(matches!(
+            parse_turn_length(&extra(21, Some(MAX_TURN_LENGTH + 1))),
+            Err(ParliaConsensusErr::InvalidTurnLength(65))
         ));
 
-        let test_cases = [
-            ((hash1, 2, 1, hash2), hash1), // Higher block wins
-            ((hash1, 1, 2, hash2), hash2), // Lower block stays
-            ((hash1, 1, 1, hash2), hash1), // Same height, lower hash wins
-            ((hash2, 1, 1, hash1), hash1), // Same height, lower hash stays
-        ];
+        // the turn length can't be read from the seal
+        assert!(matches!(
+            parse_turn_length(&extra(21, None)),
+            Err(ParliaConsensusErr::MissingTurnLength)
+        ));
+        assert!(matches!(parse_turn_length(&[]), Err(ParliaConsensusErr::MissingTurnLength)));
+    }
 
-        for ((curr_hash, curr_num, head_num, head_hash), expected) in test_cases {
-            let provider = MockProvider::new(head_num, head_hash);
-            let consensus = ParliaConsensus { provider };
-            let (head_block_hash, current_hash) =
-                consensus.canonical_head(curr_hash, curr_num).unwrap();
-            assert_eq!(head_block_hash, expected);
-            assert_eq!(current_hash, head_hash);
-        }
+    #[test]
+    fn test_parse_validators() {
+        let validators = [3, 1, 2].map(|byte| ValidatorInfo {
+            address: Address::with_last_byte(byte),
+            vote_address: VoteAddress::repeat_byte(byte),
+        });
+        let mut extra = epoch_header(0, 0, &validators, 16).extra_data.to_vec();
+
+        assert_eq!(parse_validators(&extra).unwrap(), validators);
+        assert_eq!(parse_turn_length(&extra).unwrap(), 16);
+
+        // a count beyond the extra-data, and no validators at all
+        extra[EXTRA_VANITY_LEN] = 4;
+        assert!(matches!(parse_validators(&extra), Err(ParliaConsensusErr::MissingValidators)));
+        extra[EXTRA_VANITY_LEN] = 0;
+        assert!(matches!(parse_validators(&extra), Err(ParliaConsensusErr::MissingValidators)));
+        assert!(matches!(parse_validators(&[]), Err(ParliaConsensusErr::MissingValidators)));
     }
 }
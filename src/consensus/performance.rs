@@ -0,0 +1,270 @@
+//! Per-validator proposal statistics over sliding windows of recent blocks.
+//!
+//! Every block has an in-turn validator. The block is either sealed by that validator, or
+//! another validator seals it out of turn and the in-turn validator missed its slot.
+
+use alloy_primitives::{Address, BlockNumber};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+/// Default window sizes, in blocks.
+pub const DEFAULT_PERFORMANCE_WINDOWS: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Blocks kept beyond the largest window, so that the windows are refilled after a reorg up to
+/// this deep.
+const MAX_REORG_DEPTH: usize = 64;
+
+/// Proposal counters of a validator over a window of recent blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorPerformance {
+    /// Size of the window in blocks.
+    pub window: usize,
+    /// Blocks sealed in turn.
+    pub in_turn: u64,
+    /// Blocks sealed out of turn, in the slot of another validator.
+    pub out_of_turn: u64,
+    /// In-turn slots sealed by another validator.
+    pub missed: u64,
+}
+
+impl ValidatorPerformance {
+    /// Returns the share of its in-turn slots the validator sealed, `None` if it had none.
+    pub fn uptime(&self) -> Option<f64> {
+        let slots = self.in_turn + self.missed;
+        (slots > 0).then(|| self.in_turn as f64 / slots as f64)
+    }
+}
+
+/// A block and the validators involved.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    number: BlockNumber,
+    proposer: Address,
+    in_turn: Option<Address>,
+}
+
+/// Counters of all validators within one window.
+#[derive(Debug)]
+struct Window {
+    size: usize,
+    counters: HashMap<Address, ValidatorPerformance>,
+}
+
+impl Window {
+    fn apply(&mut self, slot: &Slot, add: bool) {
+        let size = self.size;
+        let mut update = |validator, field: fn(&mut ValidatorPerformance) -> &mut u64| {
+            let counters = self
+                .counters
+                .entry(validator)
+                .or_insert_with(|| ValidatorPerformance { window: size, ..Default::default() });
+            let counter = field(counters);
+            *counter = if add { *counter + 1 } else { *counter - 1 };
+        };
+        if slot.in_turn == Some(slot.proposer) {
+            update(slot.proposer, |counters| &mut counters.in_turn);
+        } else {
+            update(slot.proposer, |counters| &mut counters.out_of_turn);
+            if let Some(in_turn) = slot.in_turn {
+                update(in_turn, |counters| &mut counters.missed);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PerformanceState {
+    /// The most recent blocks, as many as the largest window covers plus the reorg depth.
+    slots: VecDeque<Slot>,
+    windows: Vec<Window>,
+}
+
+/// Tracks the in-turn, out-of-turn and missed slots of every validator.
+///
+/// The counters are kept in memory only. The tracker is cheap to clone, all clones share the
+/// same state.
+#[derive(Debug, Clone)]
+pub struct ValidatorPerfTracker {
+    inner: Arc<Mutex<PerformanceState>>,
+}
+
+impl ValidatorPerfTracker {
+    /// Creates a tracker counting over the given window sizes.
+    pub fn new(windows: &[usize]) -> Self {
+        let windows =
+            windows.iter().map(|&size| Window { size, counters: HashMap::new() }).collect();
+        Self { inner: Arc::new(Mutex::new(PerformanceState { slots: VecDeque::new(), windows })) }
+    }
+
+    /// Records a canonical block sealed by `proposer` in the slot of `in_turn`.
+    ///
+    /// A block sealed out of turn while the in-turn validator is unknown, `None`, counts for the
+    /// proposer only. A block at or below the last recorded one replaces the blocks from its
+    /// height on, as after a reorg, the windows only cover fewer blocks after a reorg deeper than
+    /// 64 blocks. A block that leaves a gap to the last recorded one starts over.
+    pub fn on_block(&self, number: BlockNumber, proposer: Address, in_turn: Option<Address>) {
+        let mut state = self.inner.lock();
+        let PerformanceState { slots, windows } = &mut *state;
+
+        match slots.back().map(|last| last.number) {
+            Some(last) if number <= last => {
+                while slots.back().is_some_and(|slot| slot.number >= number) {
+                    let reverted = slots.pop_back().expect("not empty");
+                    for window in windows.iter_mut() {
+                        window.apply(&reverted, false);
+                        // the block that dropped out of the window when the reverted one came in
+                        if let Some(index) = slots.len().checked_sub(window.size) {
+                            window.apply(&slots[index], true);
+                        }
+                    }
+                }
+            }
+            Some(last) if number > last + 1 => {
+                slots.clear();
+                windows.iter_mut().for_each(|window| window.counters.clear());
+            }
+            _ => {}
+        }
+
+        let slot = Slot { number, proposer, in_turn };
+        slots.push_back(slot);
+        for window in windows.iter_mut() {
+            window.apply(&slot, true);
+            if let Some(index) = slots.len().checked_sub(window.size + 1) {
+                window.apply(&slots[index], false);
+            }
+        }
+        let max_window = windows.iter().map(|window| window.size).max().unwrap_or_default();
+        while slots.len() > max_window + MAX_REORG_DEPTH {
+            slots.pop_front();
+        }
+    }
+
+    /// Returns the counters of the validator for every window, smallest window first.
+    pub fn performance(&self, validator: Address) -> Vec<ValidatorPerformance> {
+        let state = self.inner.lock();
+        let mut performance: Vec<_> = state
+            .windows
+            .iter()
+            .map(|window| {
+                window
+                    .counters
+                    .get(&validator)
+                    .copied()
+                    .unwrap_or(ValidatorPerformance { window: window.size, ..Default::default() })
+            })
+            .collect();
+        performance.sort_by_key(|performance| performance.window);
+        performance
+    }
+}
+
+impl Default for ValidatorPerfTracker {
+    fn default() -> Self {
+        Self::new(&DEFAULT_PERFORMANCE_WINDOWS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays a rotation of four validators in which the third one misses every fourth of its
+    /// slots, sealed out of turn by the next validator instead.
+    fn replay(tracker: &ValidatorPerfTracker, blocks: std::ops::Range<BlockNumber>) {
+        let validators = [1, 2, 3, 4].map(Address::with_last_byte);
+        for number in blocks {
+            let in_turn = validators[number as usize % 4];
+            let missed = number % 4 == 2 && (number / 4) % 4 == 3;
+            let proposer = if missed { validators[3] } else { in_turn };
+            tracker.on_block(number, proposer, Some(in_turn));
+        }
+    }
+
+    #[test]
+    fn test_validator_performance() {
+        let tracker = ValidatorPerfTracker::new(&[160, 16]);
+        let (steady, flaky, backup) =
+            (Address::with_last_byte(1), Address::with_last_byte(3), Address::with_last_byte(4));
+        replay(&tracker, 0..1_600);
+
+        // windows are aligned to the 16 block cycle, one missed slot per cycle
+        let performance = tracker.performance(flaky);
+        assert_eq!(
+            performance,
+            vec![
+                ValidatorPerformance { window: 16, in_turn: 3, out_of_turn: 0, missed: 1 },
+                ValidatorPerformance { window: 160, in_turn: 30, out_of_turn: 0, missed: 10 },
+            ]
+        );
+        assert_eq!(performance[1].uptime(), Some(0.75));
+
+        assert_eq!(
+            tracker.performance(backup)[1],
+            ValidatorPerformance { window: 160, in_turn: 40, out_of_turn: 10, missed: 0 }
+        );
+        assert_eq!(tracker.performance(steady)[1].uptime(), Some(1.0));
+        assert_eq!(tracker.performance(Address::ZERO)[0].uptime(), None);
+    }
+
+    #[test]
+    fn test_validator_performance_reorg() {
+        let tracker = ValidatorPerfTracker::new(&[16, 160]);
+        let flaky = Address::with_last_byte(3);
+        replay(&tracker, 0..1_600);
+        let expected = tracker.performance(flaky);
+
+        // the last missed slot is reorged into an in-turn block and back
+        let missed = 1_598;
+        tracker.on_block(missed, flaky, Some(flaky));
+        assert_eq!(
+            tracker.performance(flaky),
+            vec![
+                ValidatorPerformance { window: 16, in_turn: 4, out_of_turn: 0, missed: 0 },
+                ValidatorPerformance { window: 160, in_turn: 31, out_of_turn: 0, missed: 9 },
+            ]
+        );
+        replay(&tracker, missed..1_600);
+        assert_eq!(tracker.performance(flaky), expected);
+
+        // a gap starts over
+        tracker.on_block(2_000, flaky, Some(flaky));
+        assert_eq!(
+            tracker.performance(flaky)[0],
+            ValidatorPerformance { window: 16, in_turn: 1, out_of_turn: 0, missed: 0 }
+        );
+
+        // an out-of-turn block of an unknown slot is nobody's miss
+        let backup = Address::with_last_byte(4);
+        tracker.on_block(2_001, backup, None);
+        assert_eq!(
+            tracker.performance(backup)[0],
+            ValidatorPerformance { window: 16, in_turn: 0, out_of_turn: 1, missed: 0 }
+        );
+        assert_eq!(tracker.performance(flaky)[0].missed, 0);
+    }
+
+    #[test]
+    fn test_validator_performance_reorg_fills_largest_window() {
+        let windows = [16, 160];
+        let tracker = ValidatorPerfTracker::new(&windows);
+        replay(&tracker, 0..1_600);
+
+        // the last five blocks are replaced by a single one, the windows reach back to the blocks
+        // that dropped out of them
+        let backup = Address::with_last_byte(4);
+        tracker.on_block(1_595, backup, None);
+
+        let expected = ValidatorPerfTracker::new(&windows);
+        replay(&expected, 0..1_595);
+        expected.on_block(1_595, backup, None);
+        for validator in [1, 2, 3, 4].map(Address::with_last_byte) {
+            assert_eq!(tracker.performance(validator), expected.performance(validator));
+        }
+    }
+}
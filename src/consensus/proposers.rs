@@ -0,0 +1,140 @@
+//! Optional record of the proposer of every canonical block.
+//!
+//! Epoch headers only tell which validators may propose. Who actually sealed a block, and
+//! whether it was its turn, is taken from each header's seal and difficulty.
+
+use super::DIFF_INTURN;
+use alloy_primitives::{Address, BlockNumber, B256, U256};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The effective proposer of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposerRecord {
+    /// Hash of the block.
+    pub hash: B256,
+    /// Validator that sealed the block.
+    pub proposer: Address,
+    /// Whether the block was sealed by the in-turn validator.
+    pub in_turn: bool,
+    /// Difficulty of the block.
+    pub difficulty: U256,
+}
+
+impl ProposerRecord {
+    /// Creates the record of the block `hash` sealed by `proposer` with the given difficulty.
+    pub fn new(hash: B256, proposer: Address, difficulty: U256) -> Self {
+        Self { hash, proposer, in_turn: difficulty == DIFF_INTURN, difficulty }
+    }
+}
+
+/// Keeps the proposer records of the most recent canonical blocks, keyed by block number.
+///
+/// The records follow the canonical chain: a reorg [unwinds](Self::unwind) the records of the
+/// replaced blocks before the new blocks are inserted. Once `capacity` records are kept the lowest
+/// blocks are dropped, `usize::MAX` keeps every block.
+///
+/// The archive is cheap to clone, all clones share the same records.
+#[derive(Debug, Clone)]
+pub struct ProposerArchive {
+    records: Arc<RwLock<BTreeMap<BlockNumber, ProposerRecord>>>,
+    capacity: usize,
+}
+
+impl ProposerArchive {
+    /// Default number of records kept.
+    pub const DEFAULT_CAPACITY: usize = 100_000;
+
+    /// Creates an archive keeping up to `capacity` records.
+    pub fn new(capacity: usize) -> Self {
+        Self { records: Default::default(), capacity }
+    }
+
+    /// Records the proposer of the given block.
+    pub fn insert(&self, number: BlockNumber, record: ProposerRecord) {
+        let mut records = self.records.write();
+        records.insert(number, record);
+        while records.len() > self.capacity {
+            records.pop_first();
+        }
+    }
+
+    /// Drops the records of the given block and all blocks above it.
+    pub fn unwind(&self, number: BlockNumber) {
+        self.records.write().split_off(&number);
+    }
+
+    /// Returns the proposer record of the given block, if kept.
+    pub fn get(&self, number: BlockNumber) -> Option<ProposerRecord> {
+        self.records.read().get(&number).copied()
+    }
+
+    /// Returns the number of kept records.
+    pub fn len(&self) -> usize {
+        self.records.read().len()
+    }
+
+    /// Returns `true` if no record is kept.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ProposerArchive {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::DIFF_NOTURN;
+
+    #[test]
+    fn test_proposer_archive() {
+        let archive = ProposerArchive::new(2);
+        assert!(archive.is_empty());
+
+        let (first, second) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let hash = B256::repeat_byte;
+        archive.insert(1, ProposerRecord::new(hash(1), first, DIFF_INTURN));
+        archive.insert(2, ProposerRecord::new(hash(2), second, DIFF_NOTURN));
+        assert_eq!(
+            archive.get(1),
+            Some(ProposerRecord {
+                hash: hash(1),
+                proposer: first,
+                in_turn: true,
+                difficulty: DIFF_INTURN
+            })
+        );
+        assert_eq!(
+            archive.get(2),
+            Some(ProposerRecord {
+                hash: hash(2),
+                proposer: second,
+                in_turn: false,
+                difficulty: DIFF_NOTURN
+            })
+        );
+
+        // a reorg unwinds the replaced blocks before the new ones are inserted
+        archive.unwind(2);
+        assert_eq!(archive.get(2), None);
+        archive.insert(2, ProposerRecord::new(hash(0x22), first, DIFF_INTURN));
+        assert_eq!(
+            archive.get(2).map(|record| (record.hash, record.proposer)),
+            Some((hash(0x22), first))
+        );
+        assert_eq!(archive.len(), 2);
+
+        // the lowest block is dropped once the capacity is exceeded
+        archive.insert(3, ProposerRecord::new(hash(3), second, DIFF_INTURN));
+        assert_eq!(archive.get(1), None);
+        assert!(archive.get(3).is_some_and(|record| record.in_turn));
+        assert_eq!(archive.len(), 2);
+    }
+}
@@ -0,0 +1,254 @@
+//! Validator rotation announced by canonical epoch headers.
+//!
+//! Since Luban every epoch header carries the validator set along with the BLS keys the
+//! validators vote with, since Bohr also the number of consecutive blocks each validator seals.
+//! The set announced by an epoch header takes over once half of the previous validators plus one
+//! sealed their turn after it, like the snapshot of bsc-geth does.
+
+use super::{
+    epoch_length, parse_turn_length, parse_validators, ParliaConsensusErr, BLS_PUBLIC_KEY_LEN,
+    DEFAULT_EPOCH_LENGTH, LORENTZ_EPOCH_LENGTH, MAXWELL_EPOCH_LENGTH,
+};
+use crate::hardforks::BscHardforks;
+use alloy_consensus::Header;
+use alloy_primitives::{Address, BlockNumber, FixedBytes};
+use reth_provider::HeaderProvider;
+use serde::{Deserialize, Serialize};
+
+/// BLS public key a validator signs its fast finality votes with.
+pub type VoteAddress = FixedBytes<BLS_PUBLIC_KEY_LEN>;
+
+/// A validator as listed by an epoch header since Luban.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorInfo {
+    /// Consensus address the validator seals blocks with.
+    pub address: Address,
+    /// BLS public key the validator votes with.
+    pub vote_address: VoteAddress,
+}
+
+/// Validator rotation of an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotation {
+    /// Validators in ascending order of their consensus address.
+    validators: Vec<ValidatorInfo>,
+    /// Consecutive blocks sealed by each validator.
+    turn_length: u64,
+}
+
+impl Rotation {
+    /// Creates the rotation of the given validators, each sealing `turn_length` blocks in a row.
+    ///
+    /// # Panics
+    ///
+    /// If there are no validators or the turn length is zero.
+    pub fn new(mut validators: Vec<ValidatorInfo>, turn_length: u64) -> Self {
+        assert!(!validators.is_empty() && turn_length > 0, "empty rotation");
+        validators.sort_unstable_by_key(|validator| validator.address);
+        Self { validators, turn_length }
+    }
+
+    /// Reads the rotation announced by the given epoch header.
+    ///
+    /// Headers before Bohr don't carry a turn length, each validator seals a single block. The
+    /// caller has to ensure the header is an epoch header.
+    pub fn from_epoch_header<ChainSpec: BscHardforks>(
+        chain_spec: &ChainSpec,
+        header: &Header,
+    ) -> Result<Self, ParliaConsensusErr> {
+        if !chain_spec.is_luban_active_at_block(header.number) {
+            return Err(ParliaConsensusErr::PreLubanValidators(header.number))
+        }
+        let turn_length = if chain_spec.is_bohr_active_at_timestamp(header.timestamp) {
+            parse_turn_length(&header.extra_data)?
+        } else {
+            1
+        };
+        Ok(Self::new(parse_validators(&header.extra_data)?, turn_length.into()))
+    }
+
+    /// Returns the validators in ascending order of their consensus address.
+    pub fn validators(&self) -> &[ValidatorInfo] {
+        &self.validators
+    }
+
+    /// Returns the number of consecutive blocks sealed by each validator.
+    pub const fn turn_length(&self) -> u64 {
+        self.turn_length
+    }
+
+    /// Returns the validator in turn at the given block.
+    pub fn in_turn(&self, number: BlockNumber) -> Address {
+        self.validators[(number / self.turn_length) as usize % self.validators.len()].address
+    }
+
+    /// Returns the vote address of the given validator, `None` if it isn't part of the rotation.
+    pub fn vote_address(&self, validator: Address) -> Option<VoteAddress> {
+        self.validators.iter().find(|info| info.address == validator).map(|info| info.vote_address)
+    }
+
+    /// Returns the number of blocks after an epoch block its rotation replaces this one.
+    pub fn switch_delay(&self) -> u64 {
+        (self.validators.len() as u64 / 2 + 1) * self.turn_length
+    }
+}
+
+/// Returns the epoch length in effect after the given block, starting from the `current` one.
+///
+/// A hardfork changing the epoch length doesn't take effect at once: the previous length is kept
+/// until the block following the header is an epoch block under the new length.
+pub fn next_epoch_length<ChainSpec: BscHardforks>(
+    chain_spec: &ChainSpec,
+    current: u64,
+    header: &Header,
+) -> u64 {
+    let length = epoch_length(chain_spec, header.timestamp);
+    if length != current && (header.number + 1) % length == 0 {
+        length
+    } else {
+        current
+    }
+}
+
+/// Returns the epoch length in effect at the given canonical block, see [`next_epoch_length`].
+pub fn epoch_length_at<ChainSpec, Provider>(
+    chain_spec: &ChainSpec,
+    provider: &Provider,
+    number: BlockNumber,
+) -> Result<u64, ParliaConsensusErr>
+where
+    ChainSpec: BscHardforks,
+    Provider: HeaderProvider<Header = Header>,
+{
+    // a length is in effect once a block preceding an epoch block under it activated the fork
+    for length in [MAXWELL_EPOCH_LENGTH, LORENTZ_EPOCH_LENGTH] {
+        let start = number - number % length;
+        if start > 0 && epoch_length(chain_spec, header(provider, start - 1)?.timestamp) >= length {
+            return Ok(length)
+        }
+    }
+    Ok(DEFAULT_EPOCH_LENGTH)
+}
+
+/// Returns the rotation in effect at the given canonical block.
+///
+/// The rotation of the last epoch header takes over once the rotation of the epoch before has
+/// sealed for its [switch delay](Rotation::switch_delay). The delay is shorter than an epoch, so
+/// the rotation of the epoch before is the one that announced it.
+pub fn rotation_at<ChainSpec, Provider>(
+    chain_spec: &ChainSpec,
+    provider: &Provider,
+    number: BlockNumber,
+) -> Result<Rotation, ParliaConsensusErr>
+where
+    ChainSpec: BscHardforks,
+    Provider: HeaderProvider<Header = Header>,
+{
+    let epoch = number - number % epoch_length_at(chain_spec, provider, number)?;
+    let rotation = Rotation::from_epoch_header(chain_spec, &header(provider, epoch)?)?;
+    if epoch == 0 {
+        return Ok(rotation)
+    }
+
+    let parent = epoch - 1;
+    let previous = parent - parent % epoch_length_at(chain_spec, provider, parent)?;
+    let previous = Rotation::from_epoch_header(chain_spec, &header(provider, previous)?)?;
+    Ok(if number < epoch + previous.switch_delay() { previous } else { rotation })
+}
+
+/// Returns the canonical header at the given block.
+fn header<Provider>(provider: &Provider, number: BlockNumber) -> Result<Header, ParliaConsensusErr>
+where
+    Provider: HeaderProvider<Header = Header>,
+{
+    provider.header_by_number(number)?.ok_or(ParliaConsensusErr::HeaderNotFound(number))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        chainspec::{bsc::bsc_mainnet, BscChainSpec},
+        consensus::{EXTRA_SEAL_LEN, EXTRA_VANITY_LEN},
+    };
+    use reth_provider::test_utils::MockEthProvider;
+
+    /// Returns an epoch header announcing the given validators with the given turn length.
+    pub(crate) fn epoch_header(
+        number: BlockNumber,
+        timestamp: u64,
+        validators: &[ValidatorInfo],
+        turn_length: u8,
+    ) -> Header {
+        let mut extra = vec![0u8; EXTRA_VANITY_LEN];
+        extra.push(validators.len() as u8);
+        for validator in validators {
+            extra.extend_from_slice(validator.address.as_slice());
+            extra.extend_from_slice(validator.vote_address.as_slice());
+        }
+        extra.push(turn_length);
+        extra.extend_from_slice(&[0u8; EXTRA_SEAL_LEN]);
+        Header { number, timestamp, extra_data: extra.into(), ..Default::default() }
+    }
+
+    fn validator(byte: u8) -> ValidatorInfo {
+        ValidatorInfo {
+            address: Address::repeat_byte(byte),
+            vote_address: VoteAddress::repeat_byte(byte),
+        }
+    }
+
+    #[test]
+    fn test_rotation() {
+        let rotation = Rotation::new(vec![validator(3), validator(1), validator(2)], 4);
+        assert_eq!(rotation.validators(), [validator(1), validator(2), validator(3)]);
+        assert_eq!(rotation.in_turn(0), validator(1).address);
+        assert_eq!(rotation.in_turn(3), validator(1).address);
+        assert_eq!(rotation.in_turn(4), validator(2).address);
+        assert_eq!(rotation.in_turn(12), validator(1).address);
+        assert_eq!(rotation.switch_delay(), 8);
+        assert_eq!(rotation.vote_address(validator(2).address), Some(validator(2).vote_address));
+        assert_eq!(rotation.vote_address(validator(4).address), None);
+    }
+
+    #[test]
+    fn test_rotation_at() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        let provider = MockEthProvider::default();
+        // post-Maxwell, epochs are 1000 blocks long
+        let timestamp = 1_751_250_600;
+        let (previous, current) = ([1, 2, 3].map(validator), [2, 3, 4].map(validator));
+        let headers = [
+            epoch_header(51_999_000, timestamp, &previous, 4),
+            epoch_header(52_000_000, timestamp, &current, 4),
+            Header { number: 51_998_999, timestamp, ..Default::default() },
+            Header { number: 51_999_999, timestamp, ..Default::default() },
+        ];
+        for header in headers {
+            provider.add_header(header.hash_slow(), header);
+        }
+
+        assert_eq!(epoch_length_at(&chain_spec, &provider, 52_000_007).unwrap(), 1000);
+        let rotation = rotation_at(&chain_spec, &provider, 52_000_007).unwrap();
+        assert_eq!(rotation.validators(), previous);
+        let rotation = rotation_at(&chain_spec, &provider, 52_000_008).unwrap();
+        assert_eq!(rotation.validators(), current);
+        assert_eq!(rotation.turn_length(), 4);
+
+        assert!(matches!(
+            rotation_at(&chain_spec, &provider, 52_001_000),
+            Err(ParliaConsensusErr::HeaderNotFound(52_000_999))
+        ));
+
+        // validators listed before Luban come without vote addresses
+        for number in [19_999_999, 20_000_000] {
+            let header = Header { number, ..Default::default() };
+            provider.add_header(header.hash_slow(), header);
+        }
+        assert!(matches!(
+            rotation_at(&chain_spec, &provider, 20_000_100),
+            Err(ParliaConsensusErr::PreLubanValidators(20_000_000))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! Parlia seal hash, the message a validator signs to seal a block.
+//!
+//! The hash is the keccak256 of the RLP list built from the chain id and the header fields below,
+//! in this order:
+//!
+//! - `parent_hash`, `ommers_hash`, `beneficiary`, `state_root`, `transactions_root`,
+//!   `receipts_root`, `logs_bloom`, `difficulty`, `number`, `gas_limit`, `gas_used`, `timestamp`
+//! - `extra_data` without the trailing [`EXTRA_SEAL_LEN`] bytes, the seal itself
+//! - `mix_hash`, `nonce`
+//!
+//! Since Bohr the `parent_beacon_block_root` of a BSC header is the zero hash. Headers carrying it
+//! additionally commit to `base_fee_per_gas`, `withdrawals_root`, `blob_gas_used`,
+//! `excess_blob_gas` and `parent_beacon_block_root`, followed by the `requests_hash` if the
+//! header has one (Prague). Missing optional values are encoded as the RLP empty string, like
+//! bsc-geth does for nil pointers.
+//!
+//! Since Lorentz block times are in milliseconds. `timestamp` stays in seconds and the millisecond
+//! part is carried in `mix_hash`, which is hashed as is, so no special handling is needed.
+
+use super::EXTRA_SEAL_LEN;
+use alloy_consensus::Header;
+use alloy_primitives::{keccak256, Address, B256};
+use alloy_rlp::{Encodable, EMPTY_STRING_CODE};
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Message, SecretKey, SECP256K1,
+};
+
+/// Errors returned when recovering the signer of a seal.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SealError {
+    /// The extra-data is too short to contain a seal.
+    #[error("extra-data of {0} bytes is too short to contain a seal")]
+    MissingSeal(usize),
+    /// The seal is not a valid recoverable signature of the seal hash.
+    #[error("invalid seal signature")]
+    InvalidSignature,
+}
+
+/// Returns the seal hash of the header, see the [module docs](self) for the encoded fields.
+///
+/// The last [`EXTRA_SEAL_LEN`] bytes of the extra-data are not part of the hash, so a header
+/// template with a zeroed seal placeholder hashes to the same value as the sealed header.
+pub fn seal_hash(header: &Header, chain_id: u64) -> B256 {
+    let mut payload = Vec::with_capacity(header.length() + 9);
+    chain_id.encode(&mut payload);
+    header.parent_hash.encode(&mut payload);
+    header.ommers_hash.encode(&mut payload);
+    header.beneficiary.encode(&mut payload);
+    header.state_root.encode(&mut payload);
+    header.transactions_root.encode(&mut payload);
+    header.receipts_root.encode(&mut payload);
+    header.logs_bloom.encode(&mut payload);
+    header.difficulty.encode(&mut payload);
+    header.number.encode(&mut payload);
+    header.gas_limit.encode(&mut payload);
+    header.gas_used.encode(&mut payload);
+    header.timestamp.encode(&mut payload);
+    header.extra_data[..header.extra_data.len().saturating_sub(EXTRA_SEAL_LEN)]
+        .encode(&mut payload);
+    header.mix_hash.encode(&mut payload);
+    header.nonce.encode(&mut payload);
+
+    if header.parent_beacon_block_root == Some(B256::ZERO) {
+        header.base_fee_per_gas.unwrap_or_default().encode(&mut payload);
+        match header.withdrawals_root {
+            Some(root) => root.encode(&mut payload),
+            None => payload.push(EMPTY_STRING_CODE),
+        }
+        header.blob_gas_used.unwrap_or_default().encode(&mut payload);
+        header.excess_blob_gas.unwrap_or_default().encode(&mut payload);
+        B256::ZERO.encode(&mut payload);
+        if let Some(requests_hash) = header.requests_hash {
+            requests_hash.encode(&mut payload);
+        }
+    }
+
+    let mut out = Vec::with_capacity(payload.len() + 9);
+    alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut out);
+    out.extend_from_slice(&payload);
+    keccak256(out)
+}
+
+/// Signs the seal hash of the header with the given key, the way a validator seals a block.
+///
+/// The signature is laid out like the seal in the extra-data, see [`verify_seal_signature`].
+pub fn sign_seal(header: &Header, chain_id: u64, key: &SecretKey) -> [u8; EXTRA_SEAL_LEN] {
+    let message = Message::from_digest(seal_hash(header, chain_id).0);
+    let (recovery_id, signature) =
+        SECP256K1.sign_ecdsa_recoverable(&message, key).serialize_compact();
+    let mut seal = [0; EXTRA_SEAL_LEN];
+    seal[..64].copy_from_slice(&signature);
+    seal[64] = recovery_id.to_i32() as u8;
+    seal
+}
+
+/// Signs the header with the given key and writes the seal into the last [`EXTRA_SEAL_LEN`] bytes
+/// of its extra-data.
+pub fn seal_header(header: &mut Header, chain_id: u64, key: &SecretKey) -> Result<(), SealError> {
+    let len = header.extra_data.len();
+    if len < EXTRA_SEAL_LEN {
+        return Err(SealError::MissingSeal(len));
+    }
+    let seal = sign_seal(header, chain_id, key);
+    let mut extra = header.extra_data.to_vec();
+    extra[len - EXTRA_SEAL_LEN..].copy_from_slice(&seal);
+    header.extra_data = extra.into();
+    Ok(())
+}
+
+/// Recovers the address that produced `signature` over the seal hash of the header.
+///
+/// The signature is laid out like the seal in the extra-data: 64 bytes `r || s` followed by the
+/// recovery id (0 or 1). The header's own seal is ignored, so external signers can check their
+/// output before inserting it into the extra-data.
+pub fn verify_seal_signature(
+    header: &Header,
+    chain_id: u64,
+    signature: &[u8; EXTRA_SEAL_LEN],
+) -> Result<Address, SealError> {
+    let recovery_id =
+        RecoveryId::from_i32(signature[64] as i32).map_err(|_| SealError::InvalidSignature)?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .map_err(|_| SealError::InvalidSignature)?;
+    let message = Message::from_digest(seal_hash(header, chain_id).0);
+    let public_key =
+        SECP256K1.recover_ecdsa(&message, &signature).map_err(|_| SealError::InvalidSignature)?;
+    Ok(Address::from_raw_public_key(&public_key.serialize_uncompressed()[1..]))
+}
+
+/// Recovers the validator that sealed the header from the seal in its extra-data.
+pub fn recover_seal_signer(header: &Header, chain_id: u64) -> Result<Address, SealError> {
+    let extra = &header.extra_data;
+    let seal = extra
+        .len()
+        .checked_sub(EXTRA_SEAL_LEN)
+        .and_then(|start| <&[u8; EXTRA_SEAL_LEN]>::try_from(&extra[start..]).ok())
+        .ok_or(SealError::MissingSeal(extra.len()))?;
+    verify_seal_signature(header, chain_id, seal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{constants::EMPTY_WITHDRAWALS, EMPTY_OMMER_ROOT_HASH};
+    use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
+    use alloy_primitives::{address, b256, hex, Bytes, U256};
+    use alloy_rlp::Decodable;
+
+    /// Header with a 32 byte vanity and a zeroed seal placeholder.
+    fn header(number: u64, timestamp: u64) -> Header {
+        let mut extra = vec![0xaa; 32];
+        extra.extend_from_slice(&[0; EXTRA_SEAL_LEN]);
+        Header {
+            parent_hash: B256::repeat_byte(0x01),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: Address::repeat_byte(0x02),
+            state_root: B256::repeat_byte(0x03),
+            transactions_root: B256::repeat_byte(0x04),
+            receipts_root: B256::repeat_byte(0x05),
+            difficulty: U256::from(2),
+            number,
+            gas_limit: 140_000_000,
+            gas_used: 21_000,
+            timestamp,
+            extra_data: extra.into(),
+            ..Default::default()
+        }
+    }
+
+    fn bohr_header(number: u64, timestamp: u64) -> Header {
+        Header {
+            base_fee_per_gas: Some(0),
+            withdrawals_root: Some(EMPTY_WITHDRAWALS),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..header(number, timestamp)
+        }
+    }
+
+    #[test]
+    fn test_seal_hash_per_fork() {
+        let pre_luban = header(1, 1_600_000_000);
+        assert_eq!(
+            seal_hash(&pre_luban, 56),
+            b256!("0x977bf362ab09bb7e1201845c09c90a6396f9cd64572ed45b7c7a10534e472199")
+        );
+        assert_eq!(
+            seal_hash(&pre_luban, 97),
+            b256!("0x57f6ede7f649b73eb29409b7b5094a605128bb11115dfb189d9b5a3d206f6077")
+        );
+
+        // the attestation sits between vanity and seal and is covered by the hash
+        let mut luban = header(29_020_050, 1_690_000_000);
+        let mut extra = vec![0xaa; 32];
+        extra.extend_from_slice(&[0xbb; 10]);
+        extra.extend_from_slice(&[0; EXTRA_SEAL_LEN]);
+        luban.extra_data = extra.into();
+        assert_eq!(
+            seal_hash(&luban, 56),
+            b256!("0x4bb7591b4fa8beae7e442c5a0eab90ae7818d8628db3f31246f3fa6e35d6d8ed")
+        );
+
+        let bohr = bohr_header(42_000_000, 1_727_317_200);
+        assert_eq!(
+            seal_hash(&bohr, 56),
+            b256!("0x8624dc25548b772e4372241bb5619bf1c64378a1ab7008c8603ae038da37dbe6")
+        );
+
+        // 750 milliseconds on top of the timestamp, carried in the mix hash
+        let lorentz = Header {
+            mix_hash: U256::from(750).into(),
+            requests_hash: Some(EMPTY_REQUESTS_HASH),
+            ..bohr_header(48_000_000, 1_745_903_100)
+        };
+        assert_eq!(
+            seal_hash(&lorentz, 56),
+            b256!("0x501cd756c00455610daf87e3a04ab449255d80026f5037b2017d9aaaaaf8f1db")
+        );
+    }
+
+    #[test]
+    fn test_seal_hash_ignores_seal() {
+        let bohr = bohr_header(42_000_000, 1_727_317_200);
+        let mut sealed = bohr.clone();
+        let mut extra = sealed.extra_data.to_vec();
+        let len = extra.len();
+        extra[len - EXTRA_SEAL_LEN..].fill(0xff);
+        sealed.extra_data = extra.into();
+        assert_eq!(seal_hash(&bohr, 56), seal_hash(&sealed, 56));
+
+        // the post Bohr fields are only covered with the zero parent beacon block root
+        let pre_bohr = Header { parent_beacon_block_root: None, ..bohr.clone() };
+        assert_eq!(seal_hash(&pre_bohr, 56), seal_hash(&header(42_000_000, 1_727_317_200), 56));
+        assert_ne!(seal_hash(&pre_bohr, 56), seal_hash(&bohr, 56));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let signer =
+            Address::from_raw_public_key(&key.public_key(SECP256K1).serialize_uncompressed()[1..]);
+
+        let mut header = bohr_header(42_000_000, 1_727_317_200);
+        let seal = sign_seal(&header, 56, &key);
+        assert_eq!(verify_seal_signature(&header, 56, &seal), Ok(signer));
+        assert_ne!(verify_seal_signature(&header, 97, &seal), Ok(signer));
+
+        seal_header(&mut header, 56, &key).unwrap();
+        assert_eq!(header.extra_data[header.extra_data.len() - EXTRA_SEAL_LEN..], seal);
+        assert_eq!(recover_seal_signer(&header, 56), Ok(signer));
+
+        let mut invalid = seal;
+        invalid[64] = 4;
+        assert_eq!(verify_seal_signature(&header, 56, &invalid), Err(SealError::InvalidSignature));
+
+        header.extra_data = Bytes::from_static(&[0; EXTRA_SEAL_LEN - 1]);
+        assert_eq!(
+            recover_seal_signer(&header, 56),
+            Err(SealError::MissingSeal(EXTRA_SEAL_LEN - 1))
+        );
+        assert_eq!(
+            seal_header(&mut header, 56, &key),
+            Err(SealError::MissingSeal(EXTRA_SEAL_LEN - 1))
+        );
+    }
+
+    #[test]
+    fn test_recover_seal_signer() {
+        // first header of the double sign evidence in the precompile tests, chain id 714
+        let header = Header::decode(&mut hex::decode("f9030ca01062d3d5015b9242bc193a9b0769f3d3780ecb55f97f40a752ae26d0b68cd0d8a0fae1a05fcb14bfd9b8a9f2b65007a9b6c2000de0627a73be644dd993d32342c494976ea74026e726554db657fa54763abd0c3a0aa9a0f385cc58ed297ff0d66eb5580b02853d3478ba418b1819ac659ee05df49b9794a0bf88464af369ed6b8cf02db00f0b9556ffa8d49cd491b00952a7f83431446638a00a6d0870e586a76278fbfdcedf76ef6679af18fc1f9137cfad495f434974ea81b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001820cdf830f4240830f4240846555fa64b90111d983010301846765746888676f312e32302e378664617277696e00007abd731ef8ae07b86091cb8836d58f5444b883422a18825d899035d3e6ea39ad1a50069bf0b86da8b5573dde1cb4a0a34f19ce94e0ef78ff7518c80265b8a3ca56e3c60167523590d4e8dcc324900559465fc0fa403774096614e135de280949b58a45cc96f2ba9e17f848820d41a08429d0d8b33ee72a84f750fefea846cbca54e487129c7961c680bb72309ca888820d42a08c9db14d938b19f9e2261bbeca2679945462be2b58103dfff73665d0d150fb8a804ae755e0fe64b59753f4db6308a1f679747bce186aa2c62b95fa6eeff3fbd08f3b0667e45428a54ade15bad19f49641c499b431b36f65803ea71b379e6b61de501a0232c9ba2d41b40d36ed794c306747bcbc49bf61a0f37409c18bfe2b5bef26a2d880000000000000000").unwrap().as_slice()).unwrap();
+
+        assert_eq!(
+            seal_hash(&header, 714),
+            b256!("0x838b97eab70342dcde130e8a6deed1b1f65b34ca6c697bac00e5619448dc1904")
+        );
+        assert_eq!(
+            recover_seal_signer(&header, 714),
+            Ok(address!("0x15d34aaf54267db7d7c367839aaf71a00a2c6a65"))
+        );
+    }
+}
@@ -5,7 +5,7 @@ use crate::{evm::transaction::BscTxEnv, hardforks::bsc::BscHardfork};
 use super::precompiles::BscPrecompiles;
 use reth_evm::{precompiles::PrecompilesMap, Database, EvmEnv};
 use revm::{
-    context::{BlockEnv, CfgEnv, Evm as EvmCtx, FrameStack, JournalTr},
+    context::{BlockEnv, Cfg, CfgEnv, Evm as EvmCtx, FrameStack, JournalTr},
     handler::{
         evm::{ContextDbError, FrameInitResult},
         instructions::EthInstructions,
@@ -13,6 +13,7 @@ use revm::{
     },
     inspector::InspectorEvmTr,
     interpreter::{interpreter::EthInterpreter, interpreter_action::FrameInit},
+    primitives::{CALL_STACK_LIMIT, STACK_LIMIT},
     Context, Inspector, Journal,
 };
 
@@ -35,6 +36,9 @@ pub struct BscEvm<DB: revm::database::Database, I> {
         EthFrame,
     >,
     pub inspect: bool,
+    /// Chain id of the chain spec the EVM was created for, checked before each transaction by
+    /// debug builds.
+    pub expected_chain_id: Option<u64>,
 }
 
 impl<DB: Database, I> BscEvm<DB, I> {
@@ -60,8 +64,15 @@ impl<DB: Database, I> BscEvm<DB, I> {
                 frame_stack: Default::default(),
             },
             inspect,
+            expected_chain_id: None,
         }
     }
+
+    /// Sets the chain id the environment of the EVM has to be built for.
+    pub fn with_expected_chain_id(mut self, chain_id: Option<u64>) -> Self {
+        self.expected_chain_id = chain_id;
+        self
+    }
 }
 
 impl<DB: Database, I> BscEvm<DB, I> {
@@ -74,6 +85,37 @@ impl<DB: Database, I> BscEvm<DB, I> {
     pub fn ctx_mut(&mut self) -> &mut BscContext<DB> {
         &mut self.inner.ctx
     }
+
+    /// Returns the resource limits this EVM enforces.
+    pub fn limits(&self) -> BscEvmLimits {
+        BscEvmLimits::from_cfg(&self.ctx().cfg)
+    }
+}
+
+/// EVM resource limits in effect for a [`BscEvm`], surfaced to compare them with bsc-geth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BscEvmLimits {
+    /// Maximum number of items on the interpreter stack.
+    pub stack_limit: usize,
+    /// Maximum call depth.
+    pub call_depth_limit: u64,
+    /// Maximum size of deployed contract code.
+    pub max_code_size: usize,
+    /// Maximum size of contract creation code, see EIP-3860.
+    pub max_initcode_size: usize,
+}
+
+impl BscEvmLimits {
+    /// Returns the limits resulting from the given [`CfgEnv`].
+    pub fn from_cfg(cfg: &CfgEnv<BscHardfork>) -> Self {
+        let max_code_size = cfg.max_code_size();
+        Self {
+            stack_limit: STACK_LIMIT,
+            call_depth_limit: CALL_STACK_LIMIT,
+            max_code_size,
+            max_initcode_size: max_code_size.saturating_mul(2),
+        }
+    }
 }
 
 impl<DB: Database, I> Deref for BscEvm<DB, I> {
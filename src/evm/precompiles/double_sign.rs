@@ -1,6 +1,6 @@
 //! Credits to <https://github.com/bnb-chain/revm/blob/d66170e712460ae766fc26a063f106658ce33e9d/crates/precompile/src/double_sign.rs>
 
-use crate::evm::precompiles::error::BscPrecompileError;
+use crate::{consensus::EXTRA_SEAL_LEN, evm::precompiles::error::BscPrecompileError};
 use alloy_primitives::{keccak256, BlockNumber, Bytes, ChainId, B256, B512, U256};
 use alloy_rlp::{Decodable, RlpDecodable, RlpEncodable};
 use core::cmp::Ordering;
@@ -13,8 +13,6 @@ use revm::precompile::{
 pub(crate) const DOUBLE_SIGN_EVIDENCE_VALIDATION: PrecompileWithAddress =
     PrecompileWithAddress(u64_to_address(104), double_sign_evidence_validation_run);
 
-const EXTRA_SEAL_LENGTH: usize = 65;
-
 /// Double sign evidence with two different headers.
 #[derive(Debug, RlpDecodable, RlpEncodable, PartialEq)]
 pub(crate) struct DoubleSignEvidence {
@@ -103,12 +101,12 @@ fn double_sign_evidence_validation_run(input: &[u8], gas_limit: u64) -> Precompi
         return Err(BscPrecompileError::DoubleSignInvalidEvidence.into());
     }
 
-    if header1.extra.len() < EXTRA_SEAL_LENGTH || header2.extra.len() < EXTRA_SEAL_LENGTH {
+    if header1.extra.len() < EXTRA_SEAL_LEN || header2.extra.len() < EXTRA_SEAL_LEN {
         return Err(BscPrecompileError::DoubleSignInvalidEvidence.into());
     }
 
-    let sig1 = &header1.extra[header1.extra.len() - EXTRA_SEAL_LENGTH..];
-    let sig2 = &header2.extra[header2.extra.len() - EXTRA_SEAL_LENGTH..];
+    let sig1 = &header1.extra[header1.extra.len() - EXTRA_SEAL_LEN..];
+    let sig2 = &header2.extra[header2.extra.len() - EXTRA_SEAL_LEN..];
     if sig1.eq(sig2) {
         return Err(BscPrecompileError::DoubleSignInvalidEvidence.into());
     }
@@ -156,7 +154,7 @@ fn seal_hash(header: &Header, chain_id: ChainId) -> B256 {
         gas_limit: header.gas_limit,
         gas_used: header.gas_used,
         time: header.time,
-        extra: header.extra.slice(..header.extra.len() - EXTRA_SEAL_LENGTH),
+        extra: header.extra.slice(..header.extra.len() - EXTRA_SEAL_LEN),
         mix_digest: header.mix_digest,
         nonce: header.nonce,
     };
@@ -197,3 +197,202 @@ impl Default for BscPrecompiles {
         Self::new(BscHardfork::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet, BscChainSpec},
+        node::evm::config::revm_spec_by_timestamp_and_block_number,
+    };
+    use revm::{
+        precompile::{u64_to_address, PrecompileError, PrecompileFn, PrecompileWithAddress},
+        primitives::Address,
+    };
+    use std::collections::HashSet;
+
+    /// Precompile sets of every fork, in activation order.
+    fn fork_precompiles() -> Vec<(&'static str, &'static Precompiles)> {
+        vec![
+            ("istanbul", istanbul()),
+            ("nano", nano()),
+            ("moran", moran()),
+            ("planck", planck()),
+            ("luban", luban()),
+            ("plato", plato()),
+            ("hertz", hertz()),
+            ("feynman", feynman()),
+            ("cancun", cancun()),
+            ("haber", haber()),
+            ("pascal", pascal()),
+        ]
+    }
+
+    /// All BSC specific precompiles, including the fork specific variants.
+    fn bsc_precompiles() -> Vec<PrecompileWithAddress> {
+        vec![
+            tendermint::TENDERMINT_HEADER_VALIDATION,
+            tendermint::TENDERMINT_HEADER_VALIDATION_NANO,
+            iavl::IAVL_PROOF_VALIDATION,
+            iavl::IAVL_PROOF_VALIDATION_NANO,
+            iavl::IAVL_PROOF_VALIDATION_MORAN,
+            iavl::IAVL_PROOF_VALIDATION_PLANCK,
+            iavl::IAVL_PROOF_VALIDATION_PLATO,
+            bls::BLS_SIGNATURE_VALIDATION,
+            cometbft::COMETBFT_LIGHT_BLOCK_VALIDATION_BEFORE_HERTZ,
+            cometbft::COMETBFT_LIGHT_BLOCK_VALIDATION,
+            double_sign::DOUBLE_SIGN_EVIDENCE_VALIDATION,
+            tm_secp256k1::TM_SECP256K1_SIGNATURE_RECOVER,
+        ]
+    }
+
+    /// Smallest gas limit the precompile accepts for the input without running out of gas.
+    fn charged_gas(run: PrecompileFn, input: &[u8]) -> u64 {
+        let out_of_gas =
+            |gas_limit| matches!(run(input, gas_limit), Err(PrecompileError::OutOfGas));
+        let (mut low, mut high) = (0, 1_000_000);
+        assert!(!out_of_gas(high));
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if out_of_gas(mid) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    #[test]
+    fn test_precompile_gas_snapshot() {
+        // canonical inputs, none of them is valid so that the precompiles fail fast after
+        // charging the gas
+        let inputs: [(u64, Vec<u8>); 6] = [
+            (100, vec![0; 32]),
+            (101, vec![0; 32]),
+            // message hash, signature and two public keys
+            (102, vec![0; 32 + 96 + 2 * 48]),
+            (103, vec![0; 31]),
+            (104, vec![]),
+            (105, vec![]),
+        ];
+
+        let snapshot = include_str!("gas_snapshot.txt");
+        let mut expected = snapshot
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.split_whitespace().collect::<Vec<_>>());
+
+        for (fork, precompiles) in fork_precompiles() {
+            let columns = expected.next().unwrap_or_else(|| panic!("{fork} missing in snapshot"));
+            assert_eq!(columns.len(), inputs.len() + 1);
+            assert_eq!(columns[0], fork);
+
+            for ((address, input), expected) in inputs.iter().zip(&columns[1..]) {
+                let address = u64_to_address(*address);
+                let actual = precompiles
+                    .get(&address)
+                    .map_or_else(|| "-".to_string(), |run| charged_gas(*run, input).to_string());
+                assert_eq!(actual, *expected, "{fork} gas snapshot changed for {address}");
+            }
+        }
+        assert!(expected.next().is_none(), "snapshot has more sets than tested");
+    }
+
+    #[test]
+    fn test_bsc_precompiles_do_not_shadow_ethereum_precompiles() {
+        let ethereum = Precompiles::latest();
+        for precompile in bsc_precompiles() {
+            assert!(
+                !ethereum.contains(precompile.address()),
+                "bsc precompile at {} collides with an ethereum precompile",
+                precompile.address()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fork_precompile_addresses_are_unique() {
+        let mut previous: Option<HashSet<Address>> = None;
+        for (fork, precompiles) in fork_precompiles() {
+            let addresses: HashSet<Address> = precompiles.addresses().copied().collect();
+            assert_eq!(
+                addresses.len(),
+                precompiles.len(),
+                "{fork} precompiles define an address more than once"
+            );
+
+            // later forks only replace or add precompiles
+            if let Some(previous) = previous {
+                assert!(previous.is_subset(&addresses), "{fork} precompiles dropped an address");
+            }
+            previous = Some(addresses);
+        }
+    }
+
+    #[test]
+    fn test_every_spec_resolves_to_a_known_precompile_set() {
+        let known: Vec<_> = fork_precompiles().into_iter().map(|(_, p)| p as *const _).collect();
+        for spec in [
+            BscHardfork::Frontier,
+            BscHardfork::Ramanujan,
+            BscHardfork::Niels,
+            BscHardfork::MirrorSync,
+            BscHardfork::Bruno,
+            BscHardfork::Euler,
+            BscHardfork::Nano,
+            BscHardfork::Moran,
+            BscHardfork::Gibbs,
+            BscHardfork::Planck,
+            BscHardfork::Luban,
+            BscHardfork::Plato,
+            BscHardfork::Hertz,
+            BscHardfork::HertzFix,
+            BscHardfork::Kepler,
+            BscHardfork::Feynman,
+            BscHardfork::FeynmanFix,
+            BscHardfork::Cancun,
+            BscHardfork::Haber,
+            BscHardfork::HaberFix,
+            BscHardfork::Bohr,
+            BscHardfork::Pascal,
+            BscHardfork::Lorentz,
+            BscHardfork::Maxwell,
+        ] {
+            let precompiles = BscPrecompiles::new(spec).precompiles();
+            assert!(known.contains(&(precompiles as *const _)), "{spec} has no precompile set");
+        }
+    }
+
+    #[test]
+    fn test_precompiles_around_gibbs_nano_moran() {
+        // before Kepler on both networks
+        let timestamp = 1_700_000_000;
+        let cases = [
+            // chapel: Gibbs 22800220, Nano 23482428, Moran 23603940
+            (BscChainSpec::from(bsc_testnet()), 22_800_219, "istanbul"),
+            (BscChainSpec::from(bsc_testnet()), 22_800_220, "istanbul"),
+            (BscChainSpec::from(bsc_testnet()), 23_482_427, "istanbul"),
+            (BscChainSpec::from(bsc_testnet()), 23_482_428, "nano"),
+            (BscChainSpec::from(bsc_testnet()), 23_603_939, "nano"),
+            (BscChainSpec::from(bsc_testnet()), 23_603_940, "moran"),
+            // mainnet: Nano 21962149, Moran 22107423, Gibbs 23846001
+            (BscChainSpec::from(bsc_mainnet()), 21_962_148, "istanbul"),
+            (BscChainSpec::from(bsc_mainnet()), 21_962_149, "nano"),
+            (BscChainSpec::from(bsc_mainnet()), 22_107_422, "nano"),
+            (BscChainSpec::from(bsc_mainnet()), 22_107_423, "moran"),
+            (BscChainSpec::from(bsc_mainnet()), 23_846_000, "moran"),
+            (BscChainSpec::from(bsc_mainnet()), 23_846_001, "moran"),
+        ];
+
+        for (chain_spec, block_number, expected) in cases {
+            let spec = revm_spec_by_timestamp_and_block_number(chain_spec, timestamp, block_number);
+            let resolved = BscPrecompiles::new(spec).precompiles();
+            let (name, _) = fork_precompiles()
+                .into_iter()
+                .find(|(_, precompiles)| std::ptr::eq(*precompiles, resolved))
+                .unwrap();
+            assert_eq!(name, expected, "block {block_number} resolved to {spec:?}");
+        }
+    }
+}
@@ -29,6 +29,9 @@ hardfork!(
         /// BSC `Moran` hardfork
         Moran,
         /// BSC `Gibbs` hardfork
+        ///
+        /// Only upgrades the `TokenHub` and `Staking` system contracts, there is no change to the
+        /// validator set or reward handling.
         Gibbs,
         /// BSC `Planck` hardfork
         Planck,
@@ -247,6 +250,71 @@ mod tests {
     use super::*;
     use crate::chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet};
 
+    #[test]
+    fn test_spec_id_mapping() {
+        let expected = [
+            (BscHardfork::Frontier, SpecId::MUIR_GLACIER),
+            (BscHardfork::Ramanujan, SpecId::MUIR_GLACIER),
+            (BscHardfork::Niels, SpecId::MUIR_GLACIER),
+            (BscHardfork::MirrorSync, SpecId::MUIR_GLACIER),
+            (BscHardfork::Bruno, SpecId::MUIR_GLACIER),
+            (BscHardfork::Euler, SpecId::MUIR_GLACIER),
+            (BscHardfork::Nano, SpecId::MUIR_GLACIER),
+            (BscHardfork::Moran, SpecId::MUIR_GLACIER),
+            (BscHardfork::Gibbs, SpecId::MUIR_GLACIER),
+            (BscHardfork::Planck, SpecId::MUIR_GLACIER),
+            (BscHardfork::Luban, SpecId::MUIR_GLACIER),
+            (BscHardfork::Plato, SpecId::MUIR_GLACIER),
+            (BscHardfork::Hertz, SpecId::LONDON),
+            (BscHardfork::HertzFix, SpecId::LONDON),
+            (BscHardfork::Kepler, SpecId::SHANGHAI),
+            (BscHardfork::Feynman, SpecId::SHANGHAI),
+            (BscHardfork::FeynmanFix, SpecId::SHANGHAI),
+            (BscHardfork::Cancun, SpecId::CANCUN),
+            (BscHardfork::Haber, SpecId::CANCUN),
+            (BscHardfork::HaberFix, SpecId::CANCUN),
+            (BscHardfork::Bohr, SpecId::CANCUN),
+            (BscHardfork::Pascal, SpecId::PRAGUE),
+            (BscHardfork::Lorentz, SpecId::PRAGUE),
+            (BscHardfork::Maxwell, SpecId::PRAGUE),
+        ];
+
+        // one entry per fork in declaration order, the match fails to compile once a fork is
+        // added so the table has to be extended with it
+        match BscHardfork::Frontier {
+            BscHardfork::Frontier |
+            BscHardfork::Ramanujan |
+            BscHardfork::Niels |
+            BscHardfork::MirrorSync |
+            BscHardfork::Bruno |
+            BscHardfork::Euler |
+            BscHardfork::Nano |
+            BscHardfork::Moran |
+            BscHardfork::Gibbs |
+            BscHardfork::Planck |
+            BscHardfork::Luban |
+            BscHardfork::Plato |
+            BscHardfork::Hertz |
+            BscHardfork::HertzFix |
+            BscHardfork::Kepler |
+            BscHardfork::Feynman |
+            BscHardfork::FeynmanFix |
+            BscHardfork::Cancun |
+            BscHardfork::Haber |
+            BscHardfork::HaberFix |
+            BscHardfork::Bohr |
+            BscHardfork::Pascal |
+            BscHardfork::Lorentz |
+            BscHardfork::Maxwell => {}
+        }
+        assert_eq!(expected.len(), BscHardfork::Maxwell as usize + 1);
+
+        for (i, (fork, spec_id)) in expected.into_iter().enumerate() {
+            assert_eq!(fork as usize, i, "{fork} out of order");
+            assert_eq!(SpecId::from(fork), spec_id, "{fork}");
+        }
+    }
+
     #[test]
     fn test_hardfork_activation_order_differences() {
         // Test the critical difference between mainnet and testnet activation orders
@@ -328,14 +396,14 @@ mod tests {
             BscHardfork::Moran
         );
 
-        // Block 23482427: Should be Gibbs (before Nano activation)
+        // Block 23482427: Should be Euler (Gibbs is active, but ordered after Nano and Moran)
         assert_eq!(
             crate::node::evm::config::revm_spec_by_timestamp_and_block_number(
                 testnet_spec.clone(),
                 1700000000, // Some timestamp
                 23482427
             ),
-            BscHardfork::Gibbs
+            BscHardfork::Euler
         );
 
         // Block 23482428: Should be Nano (Nano activation block)
@@ -1,3 +1,4 @@
+pub mod bsc_system;
 pub mod chainspec;
 pub mod consensus;
 mod evm;
@@ -5,3 +6,4 @@ mod hardforks;
 pub mod node;
 pub use node::primitives::{BscBlock, BscBlockBody, BscPrimitives};
 mod system_contracts;
+pub use system_contracts::{log_next_system_contract_upgrade, SystemContractUpgrade};
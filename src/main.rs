@@ -1,20 +1,29 @@
-use clap::{Args, Parser};
-use reth::{builder::NodeHandle, cli::Cli};
+use clap::Parser;
+use reth::{builder::NodeHandle, cli::Cli, rpc::builder::RethRpcModule};
 use reth_bsc::{
     chainspec::parser::BscChainSpecParser,
-    node::{consensus::BscConsensus, evm::config::BscEvmConfig, BscNode},
+    consensus::{ProposerArchive, ValidatorPerfTracker},
+    log_next_system_contract_upgrade,
+    node::{
+        consensus::BscConsensus,
+        datadir::{check_datadir, stamp_datadir},
+        evm::{config::BscEvmConfig, listener::SlashingListener},
+        rpc::{
+            BscAdminApiServer, BscAdminRpc, BscApiServer, BscDebugApiServer, BscDebugRpc, BscRpc,
+            ParliaApiServer, ParliaRpc,
+        },
+        BscArgs, BscNode,
+    },
 };
+use reth_chainspec::EthChainSpec;
+use reth_provider::{BlockHashReader, BlockReaderIdExt};
+use std::sync::Arc;
 
 // We use jemalloc for performance reasons
 #[cfg(all(feature = "jemalloc", unix))]
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-/// No Additional arguments
-#[derive(Debug, Clone, Copy, Default, Args)]
-#[non_exhaustive]
-struct NoArgs;
-
 fn main() -> eyre::Result<()> {
     reth_cli_util::sigsegv_handler::install();
 
@@ -23,14 +32,61 @@ fn main() -> eyre::Result<()> {
         std::env::set_var("RUST_BACKTRACE", "1");
     }
 
-    Cli::<BscChainSpecParser, NoArgs>::parse().run_with_components::<BscNode>(
+    Cli::<BscChainSpecParser, BscArgs>::parse().run_with_components::<BscNode>(
         |spec| (BscEvmConfig::new(spec.clone()), BscConsensus::new(spec)),
-        async move |builder, _| {
+        async move |builder, args| {
+            let datadir = builder.config().datadir().data_dir().to_path_buf();
+            let marker = check_datadir(&datadir)?;
+
             let (node, engine_handle_tx) = BscNode::new();
-            let NodeHandle { node, node_exit_future: exit_future } =
-                builder.node(node).launch().await?;
+            let slashing = SlashingListener::default();
+            let proposers = ProposerArchive::default();
+            let performance = ValidatorPerfTracker::default();
+            let node = node
+                .with_network_args(args.network)
+                .with_evm_args(args.evm)
+                .with_execution_listener(Arc::new(slashing.clone()))
+                .with_proposer_archive(proposers.clone())
+                .with_performance_tracker(performance.clone());
+            let block_import = node.rpc_block_import().clone();
+            let head_override = node.head_override().clone();
+            let peer_heads = node.peer_heads().clone();
+            let NodeHandle { node, node_exit_future: exit_future } = builder
+                .node(node)
+                .extend_rpc_modules(move |ctx| {
+                    ctx.modules.merge_configured(
+                        BscRpc::new(ctx.provider().clone(), performance, peer_heads.clone())
+                            .into_rpc(),
+                    )?;
+                    ctx.modules.merge_configured(
+                        ParliaRpc::new(ctx.provider().clone(), slashing, proposers).into_rpc(),
+                    )?;
+                    ctx.modules.merge_if_module_configured(
+                        RethRpcModule::Debug,
+                        BscDebugRpc::new(block_import).into_rpc(),
+                    )?;
+                    ctx.modules.merge_if_module_configured(
+                        RethRpcModule::Admin,
+                        BscAdminRpc::new(ctx.provider().clone(), head_override, peer_heads)
+                            .into_rpc(),
+                    )?;
+                    Ok(())
+                })
+                .launch()
+                .await?;
+
+            engine_handle_tx.send(node.beacon_engine_handle.clone());
+
+            // the launch verified the stored genesis, an unmarked datadir can be claimed now
+            if marker.is_none() {
+                if let Some(genesis) = node.provider.block_hash(0)? {
+                    stamp_datadir(&datadir, genesis, node.chain_spec().genesis_hash())?;
+                }
+            }
 
-            engine_handle_tx.send(node.beacon_engine_handle.clone()).unwrap();
+            if let Some(head) = node.provider.latest_header()? {
+                log_next_system_contract_upgrade(&node.chain_spec(), head.number, head.timestamp);
+            }
 
             exit_future.await
         },
@@ -1,6 +1,19 @@
-use crate::{hardforks::BscHardforks, node::BscNode, BscBlock, BscBlockBody, BscPrimitives};
-use alloy_consensus::Header;
-use alloy_primitives::B256;
+use crate::{
+    consensus::{
+        epoch_length, max_extra_data_size, next_epoch_length, parse_turn_length,
+        recover_seal_signer, seal_hash, ProposerArchive, ProposerRecord, Rotation, SealError,
+        ValidatorPerfTracker,
+    },
+    hardforks::BscHardforks,
+    node::{
+        evm::{verify_no_duplicate_transactions, verify_system_txs_at_end},
+        BscNode,
+    },
+    BscBlock, BscBlockBody, BscPrimitives,
+};
+use alloy_consensus::{proofs::calculate_ommers_root, Header, EMPTY_OMMER_ROOT_HASH};
+use alloy_primitives::{Address, BlockNumber, B256};
+use futures::{Stream, StreamExt};
 use reth::{
     api::FullNodeTypes,
     beacon_consensus::EthBeaconConsensus,
@@ -12,13 +25,34 @@ use reth::{
 };
 use reth_chainspec::EthChainSpec;
 use reth_primitives::{Receipt, RecoveredBlock, SealedBlock, SealedHeader};
-use reth_provider::BlockExecutionResult;
-use std::sync::Arc;
+use reth_primitives_traits::GotExpected;
+use reth_provider::{BlockExecutionResult, CanonStateNotification, CanonStateSubscriptions};
+use std::{collections::BTreeMap, sync::Arc};
+use tracing::debug;
 
 /// A basic Bsc consensus builder.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct BscConsensusBuilder;
+pub struct BscConsensusBuilder {
+    /// Records the proposer of every canonical block.
+    proposer_archive: Option<ProposerArchive>,
+    /// Counts the in-turn, out-of-turn and missed slots of the canonical blocks.
+    performance_tracker: Option<ValidatorPerfTracker>,
+}
+
+impl BscConsensusBuilder {
+    /// Records the proposer of every canonical block in the given archive.
+    pub fn with_proposer_archive(mut self, archive: ProposerArchive) -> Self {
+        self.proposer_archive = Some(archive);
+        self
+    }
+
+    /// Counts the slots of the canonical blocks per validator in the given tracker.
+    pub fn with_performance_tracker(mut self, tracker: ValidatorPerfTracker) -> Self {
+        self.performance_tracker = Some(tracker);
+        self
+    }
+}
 
 impl<Node> ConsensusBuilder<Node> for BscConsensusBuilder
 where
@@ -27,10 +61,198 @@ where
     type Consensus = Arc<dyn FullConsensus<BscPrimitives, Error = ConsensusError>>;
 
     async fn build_consensus(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Consensus> {
+        if self.proposer_archive.is_some() || self.performance_tracker.is_some() {
+            let notifications = ctx.provider().canonical_state_stream();
+            ctx.task_executor().spawn(record_canonical_proposers(
+                ctx.chain_spec(),
+                self.proposer_archive,
+                self.performance_tracker,
+                notifications,
+            ));
+        }
         Ok(Arc::new(BscConsensus::new(ctx.chain_spec())))
     }
 }
 
+/// Records the proposer of every block becoming canonical in the archive and the performance
+/// tracker.
+///
+/// The records of blocks replaced by a reorg are dropped and the rotation is rolled back before
+/// the new blocks are recorded. Missed slots are attributed once the validator rotation is known,
+/// i.e. from the first switch to the validators of an epoch header on.
+pub async fn record_canonical_proposers<ChainSpec, St>(
+    chain_spec: Arc<ChainSpec>,
+    archive: Option<ProposerArchive>,
+    performance: Option<ValidatorPerfTracker>,
+    mut stream: St,
+) where
+    ChainSpec: EthChainSpec + BscHardforks,
+    St: Stream<Item = CanonStateNotification<BscPrimitives>> + Unpin,
+{
+    let mut recorder = ProposerRecorder::new(chain_spec, archive, performance);
+    while let Some(notification) = stream.next().await {
+        let committed = notification.committed();
+        recorder.unwind(committed.first().header().number);
+        for block in committed.blocks_iter() {
+            recorder.on_block(block.hash(), block.header());
+        }
+    }
+}
+
+/// Number of rotation snapshots kept to roll the rotation back on a reorg.
+const MAX_ROTATION_SNAPSHOTS: usize = 16;
+
+/// Rotation state after a block, restored when a reorg replaces the blocks following it.
+#[derive(Debug, Clone, Default)]
+struct RotationSnapshot {
+    rotation: Option<Rotation>,
+    next_rotation: Option<(BlockNumber, Rotation)>,
+    epoch_length: Option<u64>,
+}
+
+/// Recovers the proposers of canonical blocks and follows the validator rotation.
+#[derive(Debug)]
+struct ProposerRecorder<ChainSpec> {
+    chain_spec: Arc<ChainSpec>,
+    archive: Option<ProposerArchive>,
+    performance: Option<ValidatorPerfTracker>,
+    /// Rotation in effect, unknown until the first switch.
+    rotation: Option<Rotation>,
+    /// Rotation of the last epoch header and the block it takes effect at.
+    next_rotation: Option<(BlockNumber, Rotation)>,
+    /// Epoch length in effect at the next block, unknown until a block preceding an epoch block
+    /// of the active hardforks.
+    epoch_length: Option<u64>,
+    /// Rotation state after the recent epoch blocks and epoch length changes.
+    snapshots: BTreeMap<BlockNumber, RotationSnapshot>,
+    /// Last recorded block.
+    last: Option<BlockNumber>,
+}
+
+impl<ChainSpec: EthChainSpec + BscHardforks> ProposerRecorder<ChainSpec> {
+    fn new(
+        chain_spec: Arc<ChainSpec>,
+        archive: Option<ProposerArchive>,
+        performance: Option<ValidatorPerfTracker>,
+    ) -> Self {
+        Self {
+            chain_spec,
+            archive,
+            performance,
+            rotation: None,
+            next_rotation: None,
+            epoch_length: None,
+            snapshots: BTreeMap::new(),
+            last: None,
+        }
+    }
+
+    /// Drops the records from the given block on and rolls the rotation back to the state before
+    /// it, if the block was already recorded.
+    ///
+    /// The rotation is restored from the last snapshot preceding the block. Between snapshots the
+    /// rotation only switches, which the next block catches up with. Without a snapshot, the
+    /// rotation is unknown again.
+    fn unwind(&mut self, number: BlockNumber) {
+        if let Some(archive) = &self.archive {
+            archive.unwind(number);
+        }
+        if !self.last.is_some_and(|last| number <= last) {
+            return
+        }
+        self.snapshots.split_off(&number);
+        let snapshot = self.snapshots.values().next_back().cloned().unwrap_or_default();
+        self.rotation = snapshot.rotation;
+        self.next_rotation = snapshot.next_rotation;
+        self.epoch_length = snapshot.epoch_length;
+        self.last = number.checked_sub(1);
+    }
+
+    /// Records the proposer of the block.
+    ///
+    /// The seal isn't verified against the validator set here, a header whose proposer can't be
+    /// recovered is skipped.
+    fn on_block(&mut self, hash: B256, header: &Header) {
+        self.last = Some(header.number);
+        if self.next_rotation.as_ref().is_some_and(|(switch, _)| header.number >= *switch) {
+            self.rotation = self.next_rotation.take().map(|(_, rotation)| rotation);
+        }
+        self.on_epoch(header);
+
+        let proposer = match recover_seal_signer(header, self.chain_spec.chain().id()) {
+            Ok(proposer) => proposer,
+            Err(err) => {
+                debug!(target: "consensus", number = header.number, %err, "Proposer not recorded");
+                return
+            }
+        };
+        let record = ProposerRecord::new(hash, proposer, header.difficulty);
+        if let Some(archive) = &self.archive {
+            archive.insert(header.number, record);
+        }
+        if let Some(tracker) = &self.performance {
+            // the sealed difficulty tells whether the proposer was in turn, the rotation whose
+            // slot it took otherwise
+            let in_turn = if record.in_turn {
+                Some(proposer)
+            } else {
+                self.rotation
+                    .as_ref()
+                    .map(|rotation| rotation.in_turn(header.number))
+                    .filter(|in_turn| *in_turn != proposer)
+            };
+            tracker.on_block(header.number, proposer, in_turn);
+        }
+    }
+
+    /// Schedules the rotation of an epoch header.
+    ///
+    /// The epoch length is carried from block to block, a hardfork changing it only takes effect
+    /// at the next epoch block under the new length.
+    fn on_epoch(&mut self, header: &Header) {
+        let is_epoch = self.epoch_length.is_some_and(|epoch| header.number % epoch == 0);
+        let epoch_length = match self.epoch_length {
+            Some(current) => Some(next_epoch_length(self.chain_spec.as_ref(), current, header)),
+            None => {
+                let epoch = epoch_length(self.chain_spec.as_ref(), header.timestamp);
+                ((header.number + 1) % epoch == 0).then_some(epoch)
+            }
+        };
+        let length_changed = epoch_length != self.epoch_length;
+        self.epoch_length = epoch_length;
+
+        if is_epoch && self.chain_spec.is_luban_active_at_block(header.number) {
+            match Rotation::from_epoch_header(self.chain_spec.as_ref(), header) {
+                Ok(rotation) => {
+                    // the validators of the previous epoch keep sealing until the switch
+                    let delay = self.rotation.as_ref().unwrap_or(&rotation).switch_delay();
+                    self.next_rotation = Some((header.number + delay, rotation));
+                }
+                Err(err) => {
+                    debug!(
+                        target: "consensus",
+                        number = header.number,
+                        %err,
+                        "Malformed epoch header"
+                    );
+                }
+            }
+        }
+
+        if is_epoch || length_changed {
+            let snapshot = RotationSnapshot {
+                rotation: self.rotation.clone(),
+                next_rotation: self.next_rotation.clone(),
+                epoch_length: self.epoch_length,
+            };
+            self.snapshots.insert(header.number, snapshot);
+            while self.snapshots.len() > MAX_ROTATION_SNAPSHOTS {
+                self.snapshots.pop_first();
+            }
+        }
+    }
+}
+
 /// BSC consensus implementation.
 ///
 /// Provides basic checks as outlined in the execution specs.
@@ -45,13 +267,32 @@ impl<ChainSpec: EthChainSpec + BscHardforks> BscConsensus<ChainSpec> {
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
         Self { inner: EthBeaconConsensus::new(chain_spec.clone()), chain_spec }
     }
+
+    /// Returns the seal hash of the header under the chain id of the spec.
+    pub fn seal_hash(&self, header: &Header) -> B256 {
+        seal_hash(header, self.chain_spec.chain().id())
+    }
+
+    /// Recovers the validator that sealed the header.
+    ///
+    /// The chain id is taken from the spec, so the proposer can't be recovered against the
+    /// wrong chain.
+    pub fn recover_proposer(&self, header: &Header) -> Result<Address, SealError> {
+        recover_seal_signer(header, self.chain_spec.chain().id())
+    }
 }
 
 impl<ChainSpec: EthChainSpec + BscHardforks> HeaderValidator for BscConsensus<ChainSpec> {
-    fn validate_header(&self, _header: &SealedHeader) -> Result<(), ConsensusError> {
+    fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
         // TODO: doesn't work because of extradata check
         // self.inner.validate_header(header)
 
+        let extra_len = header.extra_data.len();
+        if extra_len > max_extra_data_size(&*self.chain_spec, header.number, header.timestamp) {
+            return Err(ConsensusError::ExtraDataExceedsMax { len: extra_len })
+        }
+        validate_no_ommers(header.header(), &[])?;
+
         Ok(())
     }
 
@@ -71,6 +312,16 @@ impl<ChainSpec: EthChainSpec + BscHardforks> HeaderValidator for BscConsensus<Ch
             })
         }
 
+        // the epoch length of the hardforks active at the parent is in effect at least from the
+        // next epoch block under it on, headers that only start an epoch under the previous
+        // length aren't checked
+        if self.chain_spec.is_bohr_active_at_timestamp(header.timestamp) &&
+            header.number % epoch_length(&*self.chain_spec, parent.timestamp) == 0
+        {
+            parse_turn_length(&header.extra_data)
+                .map_err(|err| ConsensusError::Other(err.to_string()))?;
+        }
+
         // ensure that the blob gas fields for this block
         if let Some(blob_params) = self.chain_spec.blob_params_at_timestamp(header.timestamp) {
             validate_against_parent_4844(header.header(), parent.header(), blob_params)?;
@@ -95,19 +346,20 @@ impl<ChainSpec: EthChainSpec<Header = Header> + BscHardforks> Consensus<BscBlock
 
     fn validate_block_pre_execution(
         &self,
-        _block: &SealedBlock<BscBlock>,
+        block: &SealedBlock<BscBlock>,
     ) -> Result<(), ConsensusError> {
-        // Check ommers hash
-        // let ommers_hash = block.body().calculate_ommers_root();
-        // if Some(block.ommers_hash()) != ommers_hash {
-        //     return Err(ConsensusError::BodyOmmersHashDiff(
-        //         GotExpected {
-        //             got: ommers_hash.unwrap_or(EMPTY_OMMER_ROOT_HASH),
-        //             expected: block.ommers_hash(),
-        //         }
-        //         .into(),
-        //     ))
-        // }
+        let transactions = &block.body().transactions;
+        verify_no_duplicate_transactions(transactions)
+            .and_then(|_| {
+                if self.chain_spec.is_cancun_active_at_timestamp(block.timestamp) {
+                    verify_system_txs_at_end(transactions, block.beneficiary)
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(|err| ConsensusError::Other(err.to_string()))?;
+
+        validate_no_ommers(block.header(), &block.body().inner.ommers)?;
 
         // // Check transaction root
         // if let Err(error) = block.ensure_transaction_root_valid() {
@@ -136,6 +388,20 @@ impl<ChainSpec: EthChainSpec<Header = Header> + BscHardforks> FullConsensus<BscP
     }
 }
 
+/// Validates that a block neither carries nor commits to ommers, which don't exist in Parlia.
+pub fn validate_no_ommers(header: &Header, ommers: &[Header]) -> Result<(), ConsensusError> {
+    let got = if header.ommers_hash != EMPTY_OMMER_ROOT_HASH {
+        header.ommers_hash
+    } else if !ommers.is_empty() {
+        calculate_ommers_root(ommers)
+    } else {
+        return Ok(())
+    };
+    Err(ConsensusError::BodyOmmersHashDiff(
+        GotExpected { got, expected: EMPTY_OMMER_ROOT_HASH }.into(),
+    ))
+}
+
 /// Calculate the millisecond timestamp of a block header.
 /// Refer to https://github.com/bnb-chain/BEPs/blob/master/BEPs/BEP-520.md.
 pub fn calculate_millisecond_timestamp<H: alloy_consensus::BlockHeader>(header: &H) -> u64 {
@@ -160,8 +426,20 @@ pub fn calculate_millisecond_timestamp<H: alloy_consensus::BlockHeader>(header:
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_consensus::Header;
-    use alloy_primitives::B256;
+    use crate::{
+        bsc_system::SLASH_CONTRACT,
+        chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet, BscChainSpec},
+        consensus::{
+            epoch_header, seal_header, ValidatorInfo, ValidatorPerformance, VoteAddress,
+            DIFF_INTURN, DIFF_NOTURN, EXTRA_SEAL_LEN, EXTRA_VANITY_LEN, MAX_ATTESTATION_LEN,
+            MAX_TURN_LENGTH, TURN_LENGTH_SIZE, VALIDATOR_BYTES_LEN_AFTER_LUBAN,
+            VALIDATOR_NUMBER_SIZE,
+        },
+    };
+    use alloy_consensus::{BlockBody, Header, TxLegacy};
+    use alloy_primitives::{Bytes, Signature, TxKind, B256, U256};
+    use reth_primitives::TransactionSigned;
+    use secp256k1::{SecretKey, SECP256K1};
 
     #[test]
     fn test_calculate_millisecond_timestamp_without_mix_hash() {
@@ -203,4 +481,426 @@ mod tests {
         let result = calculate_millisecond_timestamp(&header);
         assert_eq!(result, timestamp * 1000 + milliseconds);
     }
+
+    #[test]
+    fn test_validate_header_extra_data_len() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let consensus = BscConsensus::new(chain_spec.clone());
+
+        // post-Maxwell epoch header with 45 validators, turn length and a full attestation
+        let (number, timestamp) = (52_000_000, 1_751_250_600);
+        let legit_len = EXTRA_VANITY_LEN +
+            VALIDATOR_NUMBER_SIZE +
+            45 * VALIDATOR_BYTES_LEN_AFTER_LUBAN +
+            TURN_LENGTH_SIZE +
+            MAX_ATTESTATION_LEN +
+            EXTRA_SEAL_LEN;
+        let turn_length_at =
+            EXTRA_VANITY_LEN + VALIDATOR_NUMBER_SIZE + 45 * VALIDATOR_BYTES_LEN_AFTER_LUBAN;
+        let header = |len: usize| {
+            let mut extra_data = vec![0u8; len];
+            extra_data[EXTRA_VANITY_LEN] = 45;
+            extra_data[turn_length_at] = 8;
+            SealedHeader::seal_slow(Header {
+                number,
+                timestamp,
+                extra_data: extra_data.into(),
+                ..Default::default()
+            })
+        };
+        assert!(consensus.validate_header(&header(legit_len)).is_ok());
+
+        let max = max_extra_data_size(&*chain_spec, number, timestamp);
+        assert!(consensus.validate_header(&header(max)).is_ok());
+        assert!(matches!(
+            consensus.validate_header(&header(max + 1)),
+            Err(ConsensusError::ExtraDataExceedsMax { len }) if len == max + 1
+        ));
+        assert!(consensus.validate_header(&header(1024 * 1024)).is_err());
+
+        // non-epoch headers don't carry validators
+        assert!(max_extra_data_size(&*chain_spec, number + 1, timestamp) < max);
+    }
+
+    #[test]
+    fn test_validate_header_turn_length() {
+        let consensus = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_mainnet())));
+        // blob gas fields are expected since Cancun
+        let parent = |number, timestamp| {
+            SealedHeader::seal_slow(Header {
+                number,
+                timestamp,
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                ..Default::default()
+            })
+        };
+        let validate = |number, parent_timestamp, turn_length: u8| {
+            let parent = parent(number - 1, parent_timestamp);
+            let mut extra_data = vec![0u8; EXTRA_VANITY_LEN];
+            extra_data.push(21);
+            extra_data.resize(extra_data.len() + 21 * VALIDATOR_BYTES_LEN_AFTER_LUBAN, 0xab);
+            extra_data.push(turn_length);
+            extra_data.resize(extra_data.len() + EXTRA_SEAL_LEN, 0);
+            let header = SealedHeader::seal_slow(Header {
+                parent_hash: parent.hash(),
+                number,
+                timestamp: parent_timestamp + 1,
+                extra_data: extra_data.into(),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                ..Default::default()
+            });
+            consensus.validate_header_against_parent(&header, &parent)
+        };
+        let is_turn_length_err = |result: Result<(), ConsensusError>| {
+            matches!(result, Err(ConsensusError::Other(err)) if err.contains("Turn length"))
+        };
+
+        // post-Maxwell, epochs are 1000 blocks long
+        let maxwell = 1_751_250_600;
+        for turn_length in [1, 3, 16, MAX_TURN_LENGTH] {
+            assert!(validate(52_000_000, maxwell, turn_length).is_ok());
+        }
+        for turn_length in [0, 2, MAX_TURN_LENGTH + 1, u8::MAX] {
+            assert!(is_turn_length_err(validate(52_000_000, maxwell, turn_length)));
+        }
+
+        // only epoch headers carry a turn length
+        assert!(validate(52_000_500, maxwell, 2).is_ok());
+
+        // the block activating Lorentz still ends an epoch of 200 blocks, the new length of 500
+        // blocks only applies once its parent is past the hardfork
+        let lorentz = 1_745_903_100;
+        assert!(validate(48_000_500, lorentz - 1, 2).is_ok());
+        assert!(is_turn_length_err(validate(48_000_500, lorentz, 2)));
+        assert!(is_turn_length_err(validate(48_000_400, lorentz - 1, 2)));
+    }
+
+    #[test]
+    fn test_validate_no_ommers() {
+        let consensus = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_mainnet())));
+        let ommer = Header { number: 1, ..Default::default() };
+        let block = |ommers_hash, ommers| {
+            SealedBlock::seal_slow(BscBlock {
+                header: Header { ommers_hash, ..Default::default() },
+                body: BscBlockBody {
+                    inner: BlockBody { transactions: Vec::new(), ommers, withdrawals: None },
+                    sidecars: None,
+                },
+            })
+        };
+
+        let valid = block(EMPTY_OMMER_ROOT_HASH, Vec::new());
+        assert!(consensus.validate_header(valid.sealed_header()).is_ok());
+        assert!(consensus.validate_block_pre_execution(&valid).is_ok());
+
+        // ommers hidden behind the empty ommers hash
+        let with_ommers = block(EMPTY_OMMER_ROOT_HASH, vec![ommer.clone()]);
+        assert!(consensus.validate_header(with_ommers.sealed_header()).is_ok());
+        assert!(matches!(
+            consensus.validate_block_pre_execution(&with_ommers),
+            Err(ConsensusError::BodyOmmersHashDiff(diff))
+                if diff.got == calculate_ommers_root(&[ommer.clone()])
+        ));
+
+        // header committing to the ommers
+        let committed = block(calculate_ommers_root(&[ommer.clone()]), vec![ommer]);
+        assert!(matches!(
+            consensus.validate_header(committed.sealed_header()),
+            Err(ConsensusError::BodyOmmersHashDiff(_))
+        ));
+        assert!(consensus.validate_block_pre_execution(&committed).is_err());
+    }
+
+    #[test]
+    fn test_validate_system_txs_at_end_since_cancun() {
+        let consensus = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_mainnet())));
+        let tx = |to, gas_price| {
+            TransactionSigned::new_unhashed(
+                TxLegacy {
+                    chain_id: Some(56),
+                    gas_price,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(to),
+                    ..Default::default()
+                }
+                .into(),
+                Signature::test_signature(),
+            )
+        };
+        let system = tx(SLASH_CONTRACT, 0);
+        let user = tx(Address::repeat_byte(0x11), 1_000_000_000);
+        let block = |timestamp| {
+            SealedBlock::seal_slow(BscBlock {
+                header: Header {
+                    timestamp,
+                    beneficiary: system.recover_signer().unwrap(),
+                    ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                    ..Default::default()
+                },
+                body: BscBlockBody {
+                    inner: BlockBody {
+                        transactions: vec![system.clone(), user.clone()],
+                        ommers: Vec::new(),
+                        withdrawals: None,
+                    },
+                    sidecars: None,
+                },
+            })
+        };
+
+        // bsc-geth accepts user transactions after system transactions before Cancun
+        assert!(consensus.validate_block_pre_execution(&block(1_690_000_000)).is_ok());
+        assert!(matches!(
+            consensus.validate_block_pre_execution(&block(1_720_000_000)),
+            Err(ConsensusError::Other(err)) if err.contains(&user.tx_hash().to_string())
+        ));
+    }
+
+    #[test]
+    fn test_validate_header_against_parent_hash() {
+        let consensus = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_mainnet())));
+
+        // pre-Cancun, so that no blob gas fields are expected
+        let parent = SealedHeader::seal_slow(Header {
+            number: 33_000_000,
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        });
+        let child = |parent_hash: B256| {
+            SealedHeader::seal_slow(Header {
+                parent_hash,
+                number: parent.number + 1,
+                timestamp: parent.timestamp + 3,
+                ..Default::default()
+            })
+        };
+
+        assert!(consensus.validate_header_against_parent(&child(parent.hash()), &parent).is_ok());
+        assert!(matches!(
+            consensus.validate_header_against_parent(&child(B256::repeat_byte(0x11)), &parent),
+            Err(ConsensusError::ParentHashMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_recover_proposer() {
+        let mainnet = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_mainnet())));
+        let testnet = BscConsensus::new(Arc::new(BscChainSpec::from(bsc_testnet())));
+        let key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let proposer =
+            Address::from_raw_public_key(&key.public_key(SECP256K1).serialize_uncompressed()[1..]);
+
+        let mut header = Header {
+            number: 1,
+            extra_data: Bytes::from(vec![0; EXTRA_VANITY_LEN + EXTRA_SEAL_LEN]),
+            ..Default::default()
+        };
+        assert_eq!(mainnet.seal_hash(&header), seal_hash(&header, 56));
+        assert_eq!(testnet.seal_hash(&header), seal_hash(&header, 97));
+
+        seal_header(&mut header, 56, &key).unwrap();
+
+        assert_eq!(mainnet.recover_proposer(&header), Ok(proposer));
+        assert_eq!(recover_seal_signer(&header, 56), Ok(proposer));
+        assert_ne!(testnet.recover_proposer(&header), Ok(proposer));
+    }
+
+    /// Returns the keys of four validators, ordered by address.
+    fn validator_keys() -> Vec<(Address, SecretKey)> {
+        let mut keys: Vec<_> = (1..=4u8)
+            .map(|byte| {
+                let key = SecretKey::from_slice(&[byte; 32]).unwrap();
+                let address = Address::from_raw_public_key(
+                    &key.public_key(SECP256K1).serialize_uncompressed()[1..],
+                );
+                (address, key)
+            })
+            .collect();
+        keys.sort_by_key(|(address, _)| *address);
+        keys
+    }
+
+    /// Returns a header at the given block sealed with the key.
+    fn sealed(number: u64, difficulty: U256, key: &SecretKey) -> Header {
+        let mut header = Header {
+            number,
+            difficulty,
+            extra_data: Bytes::from(vec![0; EXTRA_VANITY_LEN + EXTRA_SEAL_LEN]),
+            ..Default::default()
+        };
+        seal_header(&mut header, 56, key).unwrap();
+        header
+    }
+
+    #[test]
+    fn test_record_proposer() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let archive = ProposerArchive::default();
+        let mut recorder = ProposerRecorder::new(chain_spec, Some(archive.clone()), None);
+        let (proposer, key) = validator_keys().remove(0);
+        let (first, second) = (B256::repeat_byte(0x01), B256::repeat_byte(0x02));
+
+        recorder.on_block(first, &sealed(1, DIFF_INTURN, &key));
+        recorder.on_block(second, &sealed(2, DIFF_NOTURN, &key));
+        // no seal to recover the proposer from
+        recorder.on_block(B256::ZERO, &Header { number: 3, ..Default::default() });
+
+        assert_eq!(archive.get(1), Some(ProposerRecord::new(first, proposer, DIFF_INTURN)));
+        assert_eq!(archive.get(2), Some(ProposerRecord::new(second, proposer, DIFF_NOTURN)));
+        assert!(archive.get(1).is_some_and(|record| record.in_turn));
+        assert!(archive.get(2).is_some_and(|record| !record.in_turn));
+        assert_eq!(archive.get(3), None);
+    }
+
+    #[test]
+    fn test_track_validator_performance() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let tracker = ValidatorPerfTracker::new(&[64]);
+        let mut recorder = ProposerRecorder::new(chain_spec, None, Some(tracker.clone()));
+        let keys = validator_keys();
+        let validators: Vec<_> = keys.iter().map(|(address, _)| *address).collect();
+
+        // unknown rotation, the out-of-turn block counts for its proposer only
+        recorder.on_block(B256::ZERO, &sealed(99, DIFF_NOTURN, &keys[0].1));
+        assert_eq!(tracker.performance(validators[0])[0].out_of_turn, 1);
+
+        // the third validator misses every fourth of its slots, the fourth one seals it instead
+        let infos = validators.iter().map(|&address| ValidatorInfo {
+            address,
+            vote_address: VoteAddress::repeat_byte(0xab),
+        });
+        recorder.rotation = Some(Rotation::new(infos.collect(), 1));
+        for number in 100..164 {
+            let in_turn = number as usize % 4;
+            let missed = in_turn == 2 && (number / 4) % 4 == 3;
+            let (proposer, difficulty) =
+                if missed { (3, DIFF_NOTURN) } else { (in_turn, DIFF_INTURN) };
+            recorder.on_block(B256::ZERO, &sealed(number, difficulty, &keys[proposer].1));
+        }
+
+        let performance = |index: usize| tracker.performance(validators[index])[0];
+        assert_eq!(
+            performance(2),
+            ValidatorPerformance { window: 64, in_turn: 12, out_of_turn: 0, missed: 4 }
+        );
+        assert_eq!(performance(2).uptime(), Some(0.75));
+        assert_eq!(
+            performance(3),
+            ValidatorPerformance { window: 64, in_turn: 16, out_of_turn: 4, missed: 0 }
+        );
+        assert_eq!(performance(0).uptime(), Some(1.0));
+    }
+
+    #[test]
+    fn test_rotation_switch() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let mut recorder = ProposerRecorder::new(chain_spec, None, None);
+        let validators: Vec<_> = validator_keys()
+            .iter()
+            .map(|(address, _)| ValidatorInfo {
+                address: *address,
+                vote_address: VoteAddress::repeat_byte(0xab),
+            })
+            .collect();
+
+        // Maxwell epoch header announcing three validators in reverse order with turns of 4
+        let (epoch, timestamp) = (52_000_000, 1_751_250_600);
+        let announced: Vec<_> = validators[..3].iter().rev().copied().collect();
+        let header = |number| epoch_header(number, timestamp, &announced, 4);
+
+        // the switch waits for half of the validators plus one to seal their turn
+        let rotation = Rotation::new(validators[..3].to_vec(), 4);
+        recorder.on_block(B256::ZERO, &header(epoch - 1));
+        recorder.on_block(B256::ZERO, &header(epoch));
+        assert_eq!(recorder.next_rotation, Some((epoch + 8, rotation.clone())));
+        recorder.on_block(B256::ZERO, &header(epoch + 7));
+        assert_eq!(recorder.rotation, None);
+        recorder.on_block(B256::ZERO, &header(epoch + 8));
+        assert_eq!(recorder.rotation, Some(rotation.clone()));
+        assert_eq!(recorder.next_rotation, None);
+
+        // 52_000_008 / 4 % 3 == 0, each validator seals four blocks in a row
+        assert_eq!(rotation.in_turn(epoch + 8), validators[0].address);
+        assert_eq!(rotation.in_turn(epoch + 11), validators[0].address);
+        assert_eq!(rotation.in_turn(epoch + 12), validators[1].address);
+        assert_eq!(rotation.in_turn(epoch + 16), validators[2].address);
+
+        // blocks that are no epoch blocks under the Maxwell epoch length schedule nothing
+        recorder.on_block(B256::ZERO, &header(epoch + 500));
+        assert_eq!(recorder.next_rotation, None);
+    }
+
+    #[test]
+    fn test_epoch_length_across_lorentz() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let mut recorder = ProposerRecorder::new(chain_spec, None, None);
+        let validators: Vec<_> = validator_keys()[..3]
+            .iter()
+            .map(|(address, _)| ValidatorInfo {
+                address: *address,
+                vote_address: VoteAddress::repeat_byte(0xab),
+            })
+            .collect();
+        let lorentz = 1_745_903_100;
+        let mut on_block = |number, timestamp| {
+            recorder.on_block(B256::ZERO, &epoch_header(number, timestamp, &validators, 4));
+            // three validators with turns of 4 switch 8 blocks after the epoch block
+            recorder.next_rotation.as_ref().map(|(switch, _)| *switch)
+        };
+
+        // the epoch length is unknown until the block preceding an epoch block
+        assert_eq!(on_block(48_000_000, lorentz - 600), None);
+        assert_eq!(on_block(48_000_199, lorentz - 3), None);
+        assert_eq!(on_block(48_000_200, lorentz), Some(48_000_208));
+
+        // Lorentz is active, but epochs stay 200 blocks long until an epoch block of 500 blocks
+        assert_eq!(on_block(48_000_400, lorentz + 300), Some(48_000_408));
+        assert_eq!(on_block(48_000_499, lorentz + 448), None);
+        assert_eq!(on_block(48_000_500, lorentz + 450), Some(48_000_508));
+        assert_eq!(on_block(48_000_600, lorentz + 600), None);
+    }
+
+    #[test]
+    fn test_rotation_reorg() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let mut recorder = ProposerRecorder::new(chain_spec, None, None);
+        let validators: Vec<_> = validator_keys()
+            .iter()
+            .map(|(address, _)| ValidatorInfo {
+                address: *address,
+                vote_address: VoteAddress::repeat_byte(0xab),
+            })
+            .collect();
+        let timestamp = 1_751_250_600;
+        let header =
+            |number, announced: &[ValidatorInfo]| epoch_header(number, timestamp, announced, 4);
+        let (first, second, reorged) =
+            (&validators[..3], &validators[1..], [validators[0], validators[3]]);
+
+        // post-Maxwell epochs of 1000 blocks, switching 8 blocks after the epoch block
+        for number in [51_999_999, 52_000_000, 52_000_008, 52_001_000, 52_001_008] {
+            let announced = if number < 52_001_000 { first } else { second };
+            recorder.on_block(B256::ZERO, &header(number, announced));
+        }
+        assert_eq!(recorder.rotation, Some(Rotation::new(second.to_vec(), 4)));
+
+        // blocks above the last recorded one aren't a reorg
+        recorder.unwind(52_001_009);
+        assert_eq!(recorder.rotation, Some(Rotation::new(second.to_vec(), 4)));
+
+        // the epoch block is replaced, the first rotation is in effect again until the next switch
+        recorder.unwind(52_001_000);
+        assert_eq!(recorder.rotation, None);
+        assert_eq!(recorder.next_rotation, Some((52_000_008, Rotation::new(first.to_vec(), 4))));
+        recorder.on_block(B256::ZERO, &header(52_001_000, &reorged));
+        assert_eq!(recorder.rotation, Some(Rotation::new(first.to_vec(), 4)));
+        assert_eq!(recorder.next_rotation, Some((52_001_008, Rotation::new(reorged.to_vec(), 4))));
+
+        // without a snapshot before the reorg the rotation is unknown again
+        recorder.unwind(51_999_999);
+        assert!(recorder.rotation.is_none() && recorder.next_rotation.is_none());
+        assert_eq!(recorder.epoch_length, None);
+    }
 }
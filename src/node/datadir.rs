@@ -0,0 +1,298 @@
+//! Identity of the client a datadir was created by.
+//!
+//! Reth already refuses to start when the genesis hash stored in the database doesn't match the
+//! chain spec, which catches a datadir of vanilla reth for Ethereum. The marker kept here
+//! additionally rejects a datadir written by a different client or by a newer schema of this
+//! one, before the node is launched on it.
+//!
+//! A datadir is only marked once its stored genesis is known to match the chain spec, so a
+//! foreign datadir is never claimed by this client.
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Name of the marker file in the datadir.
+pub const IDENTITY_FILE: &str = "bsc-client.json";
+
+/// Client name recorded in the marker.
+pub const CLIENT_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Version of the BSC specific layout of the datadir.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades the datadir to the schema version it is registered with.
+pub type Migration = fn(&Path) -> io::Result<()>;
+
+/// Migrations by the schema version they upgrade to, in ascending order.
+const MIGRATIONS: &[(u32, Migration)] = &[];
+
+/// Client and schema version recorded in the datadir.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatadirIdentity {
+    /// Name of the client that created the datadir.
+    pub client: String,
+    /// Schema version of the datadir.
+    pub schema_version: u32,
+}
+
+impl DatadirIdentity {
+    /// Returns the identity of this build.
+    pub fn current() -> Self {
+        Self { client: CLIENT_NAME.to_string(), schema_version: SCHEMA_VERSION }
+    }
+}
+
+/// Errors of the datadir compatibility check.
+#[derive(Debug, thiserror::Error)]
+pub enum DatadirError {
+    /// The marker couldn't be read or written.
+    #[error("failed to access {path}: {source}")]
+    Io {
+        /// Path of the marker.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// The marker isn't valid JSON.
+    #[error("malformed datadir marker {path}: {source}")]
+    Malformed {
+        /// Path of the marker.
+        path: PathBuf,
+        /// The underlying error.
+        source: serde_json::Error,
+    },
+    /// The datadir was created by a different client.
+    #[error(
+        "datadir was created by {found}, expected {expected}; use a separate datadir per client"
+    )]
+    ForeignClient {
+        /// Client recorded in the marker.
+        found: String,
+        /// Client of this build.
+        expected: String,
+    },
+    /// The datadir holds a different chain.
+    #[error(
+        "datadir holds the chain with genesis {found}, expected genesis {expected}; use a \
+         separate datadir per chain"
+    )]
+    GenesisMismatch {
+        /// Genesis hash stored in the datadir.
+        found: B256,
+        /// Genesis hash of the chain spec.
+        expected: B256,
+    },
+    /// The datadir was written by a newer build.
+    #[error(
+        "datadir schema version {found} is newer than version {expected} supported by this \
+         build; upgrade the node or use a separate datadir"
+    )]
+    UnsupportedSchema {
+        /// Schema version recorded in the marker.
+        found: u32,
+        /// Schema version of this build.
+        expected: u32,
+    },
+    /// A migration failed, the datadir stays at the previous schema version.
+    #[error("migration of the datadir to schema version {version} failed: {source}")]
+    Migration {
+        /// Schema version the migration upgrades to.
+        version: u32,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+/// Checks that the datadir was created by this client and brings it to the current schema.
+///
+/// Returns `None` for a datadir without a marker, which is new or predates the marker. It is left
+/// unmarked until [`stamp_datadir`] confirmed its genesis. A datadir at an older schema version is
+/// upgraded by the registered migrations.
+pub fn check_datadir(datadir: &Path) -> Result<Option<DatadirIdentity>, DatadirError> {
+    check_datadir_with(datadir, DatadirIdentity::current(), MIGRATIONS)
+}
+
+/// Marks an unmarked datadir with the identity of this build once its stored genesis matches the
+/// chain spec.
+///
+/// A datadir holding another chain, e.g. one of vanilla reth for Ethereum, is left unmarked.
+pub fn stamp_datadir(
+    datadir: &Path,
+    genesis: B256,
+    expected_genesis: B256,
+) -> Result<DatadirIdentity, DatadirError> {
+    if genesis != expected_genesis {
+        return Err(DatadirError::GenesisMismatch { found: genesis, expected: expected_genesis })
+    }
+    let identity = DatadirIdentity::current();
+    write_identity(datadir, &identity)?;
+    Ok(identity)
+}
+
+fn check_datadir_with(
+    datadir: &Path,
+    expected: DatadirIdentity,
+    migrations: &[(u32, Migration)],
+) -> Result<Option<DatadirIdentity>, DatadirError> {
+    let path = datadir.join(IDENTITY_FILE);
+    let found = match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice::<DatadirIdentity>(&bytes)
+            .map_err(|source| DatadirError::Malformed { path: path.clone(), source })?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(DatadirError::Io { path, source }),
+    };
+
+    if found.client != expected.client {
+        return Err(DatadirError::ForeignClient { found: found.client, expected: expected.client })
+    }
+    if found.schema_version > expected.schema_version {
+        return Err(DatadirError::UnsupportedSchema {
+            found: found.schema_version,
+            expected: expected.schema_version,
+        })
+    }
+    if found.schema_version == expected.schema_version {
+        return Ok(Some(found))
+    }
+
+    // the marker is bumped after every migration, so an interrupted upgrade resumes where it
+    // stopped
+    let pending = migrations.iter().filter(|(version, _)| {
+        (found.schema_version + 1..=expected.schema_version).contains(version)
+    });
+    for (version, migrate) in pending {
+        info!(target: "reth::cli", version, "Migrating datadir");
+        migrate(datadir).map_err(|source| DatadirError::Migration { version: *version, source })?;
+        write_identity(datadir, &DatadirIdentity { schema_version: *version, ..found.clone() })?;
+    }
+    write_identity(datadir, &expected)?;
+    Ok(Some(expected))
+}
+
+fn write_identity(datadir: &Path, identity: &DatadirIdentity) -> Result<(), DatadirError> {
+    let path = datadir.join(IDENTITY_FILE);
+    let json = serde_json::to_vec_pretty(identity).expect("identity serializes");
+    fs::create_dir_all(datadir)
+        .and_then(|_| fs::write(&path, json))
+        .map_err(|source| DatadirError::Io { path, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chainspec::bsc::bsc_mainnet;
+    use reth_chainspec::MAINNET;
+
+    /// Returns an empty directory unique to the test.
+    fn datadir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bsc-datadir-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn identity(client: &str, schema_version: u32) -> DatadirIdentity {
+        DatadirIdentity { client: client.to_string(), schema_version }
+    }
+
+    #[test]
+    fn test_identity_round_trip() {
+        let dir = datadir("round-trip");
+        let genesis = bsc_mainnet().genesis_hash();
+
+        // a fresh datadir is stamped once its genesis matches, later startups accept it
+        assert_eq!(check_datadir(&dir).unwrap(), None);
+        assert_eq!(stamp_datadir(&dir, genesis, genesis).unwrap(), DatadirIdentity::current());
+        let stored: DatadirIdentity =
+            serde_json::from_slice(&fs::read(dir.join(IDENTITY_FILE)).unwrap()).unwrap();
+        assert_eq!(stored, DatadirIdentity::current());
+        assert_eq!(check_datadir(&dir).unwrap(), Some(DatadirIdentity::current()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ethereum_genesis() {
+        let dir = datadir("ethereum");
+        fs::create_dir_all(&dir).unwrap();
+        let (found, expected) = (MAINNET.genesis_hash(), bsc_mainnet().genesis_hash());
+
+        // an unmarked datadir of vanilla reth isn't claimed
+        let err = stamp_datadir(&dir, found, expected).unwrap_err();
+        assert!(matches!(
+            &err,
+            DatadirError::GenesisMismatch { found: f, expected: e } if *f == found && *e == expected
+        ));
+        assert!(err.to_string().contains(&found.to_string()));
+        assert!(!dir.join(IDENTITY_FILE).exists());
+        assert_eq!(check_datadir(&dir).unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_incompatible_datadir() {
+        let dir = datadir("incompatible");
+
+        write_identity(&dir, &identity("reth", SCHEMA_VERSION)).unwrap();
+        let err = check_datadir(&dir).unwrap_err();
+        assert!(matches!(&err, DatadirError::ForeignClient { found, .. } if found == "reth"));
+        assert!(err.to_string().contains("created by reth, expected reth_bsc"));
+
+        write_identity(&dir, &identity(CLIENT_NAME, SCHEMA_VERSION + 1)).unwrap();
+        assert!(matches!(
+            check_datadir(&dir),
+            Err(DatadirError::UnsupportedSchema { found, expected })
+                if found == SCHEMA_VERSION + 1 && expected == SCHEMA_VERSION
+        ));
+
+        fs::write(dir.join(IDENTITY_FILE), "not json").unwrap();
+        assert!(matches!(check_datadir(&dir), Err(DatadirError::Malformed { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_schema_migrations() {
+        fn migrate_2(datadir: &Path) -> io::Result<()> {
+            fs::write(datadir.join("v2"), "")
+        }
+        fn migrate_3(datadir: &Path) -> io::Result<()> {
+            fs::read(datadir.join("v2")).map(|_| ())
+        }
+        fn fail(_: &Path) -> io::Result<()> {
+            Err(io::Error::other("boom"))
+        }
+
+        let dir = datadir("migrations");
+        write_identity(&dir, &identity(CLIENT_NAME, 1)).unwrap();
+
+        // a failed migration keeps the marker at the last completed version
+        let migrations: &[(u32, Migration)] = &[(2, migrate_2), (3, fail)];
+        assert!(matches!(
+            check_datadir_with(&dir, identity(CLIENT_NAME, 3), migrations),
+            Err(DatadirError::Migration { version: 3, .. })
+        ));
+        let stored: DatadirIdentity =
+            serde_json::from_slice(&fs::read(dir.join(IDENTITY_FILE)).unwrap()).unwrap();
+        assert_eq!(stored, identity(CLIENT_NAME, 2));
+
+        // the upgrade resumes with the pending migrations only
+        let migrations: &[(u32, Migration)] = &[(2, fail), (3, migrate_3)];
+        assert_eq!(
+            check_datadir_with(&dir, identity(CLIENT_NAME, 3), migrations).unwrap(),
+            Some(identity(CLIENT_NAME, 3))
+        );
+        assert_eq!(
+            check_datadir_with(&dir, identity(CLIENT_NAME, 3), &[]).unwrap(),
+            Some(identity(CLIENT_NAME, 3))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
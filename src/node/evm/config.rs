@@ -1,7 +1,7 @@
 use super::{executor::BscBlockExecutor, factory::BscEvmFactory};
 use crate::{
     chainspec::BscChainSpec,
-    evm::transaction::BscTxEnv,
+    evm::{api::BscEvmLimits, transaction::BscTxEnv},
     hardforks::{bsc::BscHardfork, BscHardforks},
     system_contracts::SystemContract,
     BscPrimitives,
@@ -46,7 +46,8 @@ impl BscEvmConfig {
 
     /// Creates a new Ethereum EVM configuration.
     pub fn bsc(chain_spec: Arc<BscChainSpec>) -> Self {
-        Self::new_with_evm_factory(chain_spec, BscEvmFactory::default())
+        let evm_factory = BscEvmFactory::new(chain_spec.chain().id());
+        Self::new_with_evm_factory(chain_spec, evm_factory)
     }
 }
 
@@ -63,10 +64,23 @@ impl BscEvmConfig {
         }
     }
 
+    /// Makes the block executors skip the system contract upgrades, see
+    /// [`BscBlockExecutorFactory::with_skip_system_contract_upgrades`].
+    #[cfg(any(test, debug_assertions))]
+    pub fn with_skip_system_contract_upgrades(mut self, skip: bool) -> Self {
+        self.executor_factory = self.executor_factory.with_skip_system_contract_upgrades(skip);
+        self
+    }
+
     /// Returns the chain spec associated with this configuration.
     pub const fn chain_spec(&self) -> &Arc<BscChainSpec> {
         self.executor_factory.spec()
     }
+
+    /// Returns the EVM resource limits in effect for the given header.
+    pub fn evm_limits(&self, header: &Header) -> BscEvmLimits {
+        BscEvmLimits::from_cfg(&self.evm_env(header).cfg_env)
+    }
 }
 
 /// Ethereum block executor factory.
@@ -82,13 +96,32 @@ pub struct BscBlockExecutorFactory<
     spec: Spec,
     /// EVM factory.
     evm_factory: EvmFactory,
+    /// Whether created executors skip the system contract upgrades, debugging aid only.
+    #[cfg(any(test, debug_assertions))]
+    skip_system_contract_upgrades: bool,
 }
 
 impl<R, Spec, EvmFactory> BscBlockExecutorFactory<R, Spec, EvmFactory> {
     /// Creates a new [`BscBlockExecutorFactory`] with the given spec, [`EvmFactory`], and
     /// [`ReceiptBuilder`].
     pub const fn new(receipt_builder: R, spec: Spec, evm_factory: EvmFactory) -> Self {
-        Self { receipt_builder, spec, evm_factory }
+        Self {
+            receipt_builder,
+            spec,
+            evm_factory,
+            #[cfg(any(test, debug_assertions))]
+            skip_system_contract_upgrades: false,
+        }
+    }
+
+    /// Makes the created executors skip the system contract upgrades.
+    ///
+    /// See [`BscBlockExecutor::with_skip_system_contract_upgrades`], never enable this on a
+    /// syncing node. Only available in debug builds.
+    #[cfg(any(test, debug_assertions))]
+    pub const fn with_skip_system_contract_upgrades(mut self, skip: bool) -> Self {
+        self.skip_system_contract_upgrades = skip;
+        self
     }
 
     /// Exposes the receipt builder.
@@ -129,13 +162,17 @@ where
         DB: alloy_evm::Database + 'a,
         I: Inspector<<Self::EvmFactory as EvmFactory>::Context<&'a mut State<DB>>> + 'a,
     {
-        BscBlockExecutor::new(
+        let executor = BscBlockExecutor::new(
             evm,
             ctx,
             self.spec().clone(),
             self.receipt_builder(),
             SystemContract::new(self.spec().clone()),
-        )
+        );
+        #[cfg(any(test, debug_assertions))]
+        let executor =
+            executor.with_skip_system_contract_upgrades(self.skip_system_contract_upgrades);
+        executor
     }
 }
 
@@ -170,6 +207,8 @@ where
         // configure evm env based on parent block
         let mut cfg_env =
             CfgEnv::new().with_chain_id(self.chain_spec().chain().id()).with_spec(spec);
+        cfg_env.limit_contract_code_size =
+            Some(self.chain_spec().max_code_size(header.number(), header.timestamp()));
 
         if let Some(blob_params) = &blob_params {
             cfg_env.set_max_blobs_per_tx(blob_params.max_blobs_per_tx);
@@ -218,10 +257,15 @@ where
         );
 
         // configure evm env based on parent block
-        let cfg_env =
+        let mut cfg_env =
             CfgEnv::new().with_chain_id(self.chain_spec().chain().id()).with_spec(spec_id);
+        cfg_env.limit_contract_code_size =
+            Some(self.chain_spec().max_code_size(parent.number() + 1, attributes.timestamp));
 
         let blob_params = self.chain_spec().blob_params_at_timestamp(attributes.timestamp);
+        if let Some(blob_params) = &blob_params {
+            cfg_env.set_max_blobs_per_tx(blob_params.max_blobs_per_tx);
+        }
 
         // if the parent block did not have excess blob gas (i.e. it was pre-cancun), but it is
         // cancun now, we need to set the excess blob gas to the default value(0)
@@ -238,25 +282,22 @@ where
             self.chain_spec().base_fee_params_at_timestamp(attributes.timestamp),
         );
 
-        let mut gas_limit = U256::from(parent.gas_limit);
+        // validators vote the gas limit from the parent towards the target of the hardforks
+        // active at the block, like bsc-geth does by default, the attributes' gas limit is ignored
+        let gas_limit = self.chain_spec().next_gas_limit(
+            parent.gas_limit,
+            parent.number + 1,
+            attributes.timestamp,
+        );
 
-        // If we are on the London fork boundary, we need to multiply the parent's gas limit by the
-        // elasticity multiplier to get the new gas limit.
+        // If we are on the London fork boundary, set the base fee to the initial base fee from the
+        // EIP-1559 spec.
         if self
             .chain_spec()
             .inner
             .fork(EthereumHardfork::London)
             .transitions_at_block(parent.number + 1)
         {
-            let elasticity_multiplier = self
-                .chain_spec()
-                .base_fee_params_at_timestamp(attributes.timestamp)
-                .elasticity_multiplier;
-
-            // multiply the gas limit by the elasticity multiplier
-            gas_limit *= U256::from(elasticity_multiplier);
-
-            // set the base fee to the initial base fee from the EIP-1559 spec
             basefee = Some(EIP1559_INITIAL_BASE_FEE)
         }
 
@@ -266,7 +307,7 @@ where
             timestamp: U256::from(attributes.timestamp),
             difficulty: U256::ZERO,
             prevrandao: Some(attributes.prev_randao),
-            gas_limit: attributes.gas_limit,
+            gas_limit,
             // calculate basefee based on parent block's gas usage
             basefee: basefee.unwrap_or_default(),
             // calculate excess gas based on parent block's blob gas usage
@@ -357,6 +398,13 @@ pub fn revm_spec_by_timestamp_and_block_number(
         ];
         forks.sort_by(|a, b| b.0.cmp(&a.0));
         for &(_, fork) in &forks {
+            // Gibbs is ordered after Nano and Moran and specs are compared with `>=`, e.g. to
+            // pick the precompiles, but on chapel it activates before both of them. It only
+            // upgrades system contracts, which don't depend on the spec, so it's only resolved
+            // once Moran is active.
+            if fork == BscHardfork::Gibbs && !chain_spec.is_moran_active_at_block(block_number) {
+                continue
+            }
             if chain_spec.bsc_fork_activation(fork).active_at_block(block_number) {
                 return fork;
             }
@@ -376,3 +424,301 @@ pub fn revm_spec_by_timestamp_and_block_number(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet};
+    use alloy_eips::eip4844::VERSIONED_HASH_VERSION_KZG;
+    use alloy_primitives::{Address, TxKind, B256};
+    use reth_evm::Evm;
+    use revm::{
+        context::{
+            result::{EVMError, InvalidTransaction},
+            TxEnv,
+        },
+        database::EmptyDB,
+        inspector::NoOpInspector,
+    };
+
+    fn mainnet_config() -> BscEvmConfig {
+        BscEvmConfig::new(Arc::new(BscChainSpec::from(bsc_mainnet())))
+    }
+
+    #[test]
+    fn test_max_code_size_in_evm_env() {
+        let config = mainnet_config();
+        let head = config.chain_spec().head();
+        let header =
+            Header { number: head.number, timestamp: head.timestamp, ..Default::default() };
+
+        let env = config.evm_env(&header);
+        assert_eq!(env.cfg_env.limit_contract_code_size, Some(24_576));
+
+        let attributes = NextBlockEnvAttributes {
+            timestamp: head.timestamp + 1,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: header.gas_limit,
+            parent_beacon_block_root: None,
+            withdrawals: None,
+        };
+        let env = config.next_evm_env(&header, &attributes).unwrap();
+        assert_eq!(env.cfg_env.limit_contract_code_size, Some(24_576));
+    }
+
+    #[test]
+    fn test_blob_gasprice_uses_cancun_params() {
+        let config = mainnet_config();
+        let head = config.chain_spec().head();
+
+        // pre-Cancun blocks have no blob fee
+        let header = Header { number: 39_000_000, timestamp: 1_718_863_499, ..Default::default() };
+        assert!(config.evm_env(&header).block_env.blob_excess_gas_and_price.is_none());
+
+        // BSC keeps the Cancun update fraction after Prague, so the blob base fee revm checks
+        // `max_fee_per_blob_gas` against is never below Ethereum's for the same excess
+        for excess_blob_gas in [0, 3 * 131_072, 10_000_000, 100_000_000] {
+            let header = Header {
+                number: head.number,
+                timestamp: head.timestamp,
+                excess_blob_gas: Some(excess_blob_gas),
+                ..Default::default()
+            };
+            let blob = config.evm_env(&header).block_env.blob_excess_gas_and_price.unwrap();
+            assert_eq!(blob.excess_blob_gas, excess_blob_gas);
+            assert_eq!(blob.blob_gasprice, BlobParams::cancun().calc_blob_fee(excess_blob_gas));
+            if excess_blob_gas > 0 {
+                assert!(blob.blob_gasprice >= BlobParams::prague().calc_blob_fee(excess_blob_gas));
+            }
+        }
+    }
+
+    #[test]
+    fn test_evm_limits() {
+        let config = mainnet_config();
+        let expected = BscEvmLimits {
+            stack_limit: 1024,
+            call_depth_limit: 1024,
+            max_code_size: 24_576,
+            max_initcode_size: 49_152,
+        };
+
+        // genesis, Shanghai (EIP-3860) and the latest fork share the same limits
+        let head = config.chain_spec().head();
+        let blocks = [(0, 0), (34_140_700, 1_705_996_800), (head.number, head.timestamp)];
+        for (number, timestamp) in blocks {
+            let header = Header { number, timestamp, ..Default::default() };
+            assert_eq!(config.evm_limits(&header), expected);
+
+            let evm = config.evm_factory().create_evm(EmptyDB::default(), config.evm_env(&header));
+            assert_eq!(evm.limits(), expected);
+        }
+    }
+
+    #[test]
+    fn test_evm_chain_id_and_spec() {
+        let mainnet = mainnet_config();
+        let testnet = BscEvmConfig::new(Arc::new(BscChainSpec::from(bsc_testnet())));
+        let head = testnet.chain_spec().head();
+        let cases = [
+            (mainnet, 56, (47_618_307, 1_742_436_600), Some(BscHardfork::Pascal)),
+            (testnet, 97, (head.number, head.timestamp), None),
+        ];
+
+        for (config, chain_id, (number, timestamp), expected_spec) in cases {
+            let header = Header { number, timestamp, ..Default::default() };
+            let spec = revm_spec_by_timestamp_and_block_number(
+                config.chain_spec().clone(),
+                timestamp,
+                number,
+            );
+            if let Some(expected_spec) = expected_spec {
+                assert_eq!(spec, expected_spec);
+            }
+
+            // the environment is always derived from the chain spec, no fixups needed
+            let evm = config.evm_with_env(EmptyDB::default(), config.evm_env(&header));
+            assert_eq!(
+                (evm.chain_id(), evm.cfg.spec, evm.expected_chain_id),
+                (chain_id, spec, Some(chain_id))
+            );
+
+            let evm = config.evm_with_env_and_inspector(
+                EmptyDB::default(),
+                config.evm_env(&header),
+                NoOpInspector {},
+            );
+            assert_eq!(
+                (evm.chain_id(), evm.cfg.spec, evm.expected_chain_id),
+                (chain_id, spec, Some(chain_id))
+            );
+
+            let evm = config.evm_factory().create_evm(EmptyDB::default(), config.evm_env(&header));
+            assert_eq!(
+                (evm.chain_id(), evm.cfg.spec, evm.expected_chain_id),
+                (chain_id, spec, Some(chain_id))
+            );
+
+            let attributes = NextBlockEnvAttributes {
+                timestamp: timestamp + 1,
+                suggested_fee_recipient: Address::ZERO,
+                prev_randao: B256::ZERO,
+                gas_limit: header.gas_limit,
+                parent_beacon_block_root: None,
+                withdrawals: None,
+            };
+            let env = config.next_evm_env(&header, &attributes).unwrap();
+            assert_eq!(env.cfg_env.chain_id, chain_id);
+            assert_eq!(
+                env.cfg_env.spec,
+                revm_spec_by_timestamp_and_block_number(
+                    config.chain_spec().clone(),
+                    timestamp + 1,
+                    number + 1,
+                )
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "EVM environment built for another chain")]
+    fn test_evm_rejects_environment_of_another_chain() {
+        let mainnet = mainnet_config();
+        let testnet = BscEvmConfig::new(Arc::new(BscChainSpec::from(bsc_testnet())));
+        let env = testnet.evm_env(&Header::default());
+        let mut evm = mainnet.evm_with_env(EmptyDB::default(), env);
+        let _ = evm.transact_raw(BscTxEnv::default());
+    }
+
+    #[test]
+    fn test_next_block_gas_limit_votes_towards_target() {
+        let config = mainnet_config();
+        // post-Hertz, the target is 30M whatever the attributes ask for
+        let parent = |gas_limit| Header {
+            number: 33_000_000,
+            timestamp: 1_700_000_000,
+            gas_limit,
+            ..Default::default()
+        };
+        let attributes = NextBlockEnvAttributes {
+            timestamp: 1_700_000_003,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: 100_000_000,
+            parent_beacon_block_root: None,
+            withdrawals: None,
+        };
+        let gas_limit =
+            |parent| config.next_evm_env(&parent, &attributes).unwrap().block_env.gas_limit;
+
+        assert_eq!(config.chain_spec().preferred_gas_limit(33_000_001, 1_700_000_003), 30_000_000);
+        assert_eq!(gas_limit(parent(40_000_000)), 40_000_000 - (40_000_000 / 256 - 1));
+        assert_eq!(gas_limit(parent(20_000_000)), 20_000_000 + (20_000_000 / 256 - 1));
+        assert_eq!(gas_limit(parent(30_100_000)), 30_000_000);
+        assert_eq!(gas_limit(parent(30_000_000)), 30_000_000);
+    }
+
+    #[test]
+    fn test_next_block_gas_limit_converges_across_lorentz_and_maxwell() {
+        let config = mainnet_config();
+        let (lorentz, maxwell) = (1_745_903_100, 1_751_250_600);
+        let next = |parent: &Header, timestamp| {
+            let attributes = NextBlockEnvAttributes {
+                timestamp,
+                suggested_fee_recipient: Address::ZERO,
+                prev_randao: B256::ZERO,
+                gas_limit: parent.gas_limit,
+                parent_beacon_block_root: None,
+                withdrawals: None,
+            };
+            let gas_limit = config.next_evm_env(parent, &attributes).unwrap().block_env.gas_limit;
+            Header { number: parent.number + 1, timestamp, gas_limit, ..Default::default() }
+        };
+
+        // the 30M target holds until Lorentz activates
+        let mut parent = Header {
+            number: 48_000_000,
+            timestamp: lorentz - 2,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        parent = next(&parent, lorentz - 1);
+        assert_eq!(parent.gas_limit, 30_000_000);
+
+        // each activation moves the target, the gas limit follows at the maximum rate
+        for (activation, target) in [(lorentz, 70_000_000), (maxwell, 140_000_000)] {
+            let mut timestamp = activation;
+            while parent.gas_limit < target {
+                let block = next(&parent, timestamp);
+                let step = parent.gas_limit / 256 - 1;
+                assert_eq!(block.gas_limit, (parent.gas_limit + step).min(target));
+                parent = block;
+                timestamp += 1;
+            }
+            assert_eq!(next(&parent, timestamp).gas_limit, target);
+        }
+    }
+
+    #[test]
+    fn test_max_blobs_per_tx_from_chain_spec() {
+        let config = mainnet_config();
+        let head = config.chain_spec().head();
+        let blob_params = config.chain_spec().blob_params_at_timestamp(head.timestamp).unwrap();
+        let max_blobs = blob_params.max_blobs_per_tx;
+        let header = Header {
+            number: head.number,
+            timestamp: head.timestamp,
+            gas_limit: 140_000_000,
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let attributes = NextBlockEnvAttributes {
+            timestamp: head.timestamp + 1,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: header.gas_limit,
+            parent_beacon_block_root: None,
+            withdrawals: None,
+        };
+
+        // both the executed and the built blocks cap the blobs per transaction
+        let env = config.evm_env(&header);
+        assert_eq!(env.cfg_env.max_blobs_per_tx, Some(max_blobs));
+        let next_env = config.next_evm_env(&header, &attributes).unwrap();
+        assert_eq!(next_env.cfg_env.max_blobs_per_tx, Some(max_blobs));
+
+        // pre-Cancun blocks carry no blobs at all
+        let header = Header { number: 39_000_000, timestamp: 1_718_863_499, ..Default::default() };
+        assert_eq!(config.evm_env(&header).cfg_env.max_blobs_per_tx, None);
+
+        let mut versioned_hash = B256::ZERO;
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        for env in [env, next_env] {
+            let mut evm = config.evm_with_env(EmptyDB::default(), env);
+            let mut transact = |blobs: u64| {
+                evm.transact(BscTxEnv::new(TxEnv {
+                    tx_type: 3,
+                    kind: TxKind::Call(Address::repeat_byte(0x11)),
+                    gas_limit: 21_000,
+                    chain_id: Some(56),
+                    gas_priority_fee: Some(0),
+                    blob_hashes: vec![versioned_hash; blobs as usize],
+                    max_fee_per_blob_gas: 1,
+                    ..Default::default()
+                }))
+            };
+
+            assert!(matches!(
+                transact(max_blobs + 1),
+                Err(EVMError::Transaction(InvalidTransaction::TooManyBlobs { .. }))
+            ));
+            // at the cap the transaction passes the blob checks and fails for lack of funds
+            assert!(matches!(
+                transact(max_blobs),
+                Err(EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee { .. }))
+            ));
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use alloy_primitives::TxHash;
+use reth_evm::execute::BlockExecutionError;
+
+/// BSC specific block execution errors.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BscBlockExecutionError {
+    /// The same transaction is included more than once in a block.
+    #[error("duplicate transaction {hash} in block")]
+    DuplicateTransaction {
+        /// Hash of the repeated transaction.
+        hash: TxHash,
+    },
+    /// A user transaction follows a system transaction, system transactions close the block.
+    #[error("transaction {hash} follows a system transaction")]
+    SystemTxNotAtEnd {
+        /// Hash of the user transaction.
+        hash: TxHash,
+    },
+}
+
+impl From<BscBlockExecutionError> for BlockExecutionError {
+    fn from(err: BscBlockExecutionError) -> Self {
+        Self::other(err)
+    }
+}
@@ -1,21 +1,24 @@
-use super::patch::{
-    patch_chapel_after_tx, patch_chapel_before_tx, patch_mainnet_after_tx, patch_mainnet_before_tx,
+use super::{
+    error::BscBlockExecutionError,
+    patch::{
+        is_hertz_patch_block, patch_chapel_after_tx, patch_chapel_before_tx,
+        patch_mainnet_after_tx, patch_mainnet_before_tx,
+    },
 };
 use crate::{
+    bsc_system::{
+        is_system_transaction, SystemCallKind, STAKE_HUB_CONTRACT, SYSTEM_REWARD_CONTRACT,
+    },
+    chainspec::BscChainSpec,
     consensus::{MAX_SYSTEM_REWARD, SYSTEM_ADDRESS, SYSTEM_REWARD_PERCENT},
     evm::transaction::BscTxEnv,
     hardforks::BscHardforks,
-    system_contracts::{
-        get_upgrade_system_contracts, is_system_transaction, SystemContract, STAKE_HUB_CONTRACT,
-        SYSTEM_REWARD_CONTRACT,
-    },
+    system_contracts::{get_upgrade_system_contracts, is_invoke_system_contract, SystemContract},
 };
 use alloy_consensus::{Transaction, TxReceipt};
 use alloy_eips::{eip7685::Requests, Encodable2718};
 use alloy_evm::{block::{ExecutableTx, StateChangeSource}, eth::receipt_builder::ReceiptBuilderCtx};
-use alloy_primitives::{uint, Address, TxKind, U256, BlockNumber, Bytes};
-use alloy_sol_macro::sol;
-use alloy_sol_types::SolCall;
+use alloy_primitives::{Address, TxKind, U256, BlockNumber, Bytes};
 use reth_chainspec::{EthChainSpec, EthereumHardforks, Hardforks};
 use reth_evm::{
     block::{BlockValidationError, CommitChanges},
@@ -36,10 +39,53 @@ use revm::{
     state::Bytecode,
     Database as _, DatabaseCommit,
 };
-use tracing::debug;
+use std::collections::HashSet;
+use tracing::{debug, warn};
 use alloy_eips::eip2935::{HISTORY_STORAGE_ADDRESS, HISTORY_STORAGE_CODE};
 use alloy_primitives::keccak256;
 
+/// Ensures no transaction is included more than once in the block.
+///
+/// A repeated transaction would fail its nonce check on the second execution, but only after
+/// being accounted for, so such blocks are rejected upfront.
+pub fn verify_no_duplicate_transactions(
+    transactions: &[TransactionSigned],
+) -> Result<(), BscBlockExecutionError> {
+    let mut seen = HashSet::with_capacity(transactions.len());
+    for tx in transactions {
+        if !seen.insert(*tx.tx_hash()) {
+            return Err(BscBlockExecutionError::DuplicateTransaction { hash: *tx.tx_hash() });
+        }
+    }
+    Ok(())
+}
+
+/// Ensures no user transaction follows a system transaction in the block.
+///
+/// System transactions are collected during execution and applied once the block is finished, a
+/// user transaction following one would run before it. Like bsc-geth, the rule is only enforced
+/// since Cancun, the caller has to check the hardfork. Only transactions that look like system
+/// transactions have their signer recovered, so the scan is cheap for regular blocks.
+pub fn verify_system_txs_at_end(
+    transactions: &[TransactionSigned],
+    coinbase: Address,
+) -> Result<(), BscBlockExecutionError> {
+    let is_system = |tx: &TransactionSigned| {
+        tx.max_fee_per_gas() == 0 &&
+            tx.to().is_some_and(|to| is_invoke_system_contract(&to)) &&
+            tx.recover_signer().is_ok_and(|signer| is_system_transaction(tx, signer, coinbase))
+    };
+    let mut seen_system_tx = false;
+    for tx in transactions {
+        if is_system(tx) {
+            seen_system_tx = true;
+        } else if seen_system_tx {
+            return Err(BscBlockExecutionError::SystemTxNotAtEnd { hash: *tx.tx_hash() });
+        }
+    }
+    Ok(())
+}
+
 pub struct BscBlockExecutor<'a, EVM, Spec, R: ReceiptBuilder>
 where
     Spec: EthChainSpec,
@@ -64,6 +110,9 @@ where
     system_caller: SystemCaller<Spec>,
     /// state hook
     hook: Option<Box<dyn OnStateHook>>,
+    /// Debugging aid, skips the system contract upgrades when set.
+    #[cfg(any(test, debug_assertions))]
+    skip_system_contract_upgrades: bool,
 }
 
 impl<'a, DB, EVM, Spec, R: ReceiptBuilder> BscBlockExecutor<'a, EVM, Spec, R>
@@ -102,11 +151,35 @@ where
             _ctx,
             system_caller: SystemCaller::new(spec_clone),
             hook: None,
+            #[cfg(any(test, debug_assertions))]
+            skip_system_contract_upgrades: false,
         }
     }
 
+    /// Skips the system contract upgrades of hardfork transitions.
+    ///
+    /// This is a debugging aid only: the resulting state root is expected to mismatch on upgrade
+    /// blocks, comparing it against a regular execution tells whether a mismatch stems from the
+    /// upgraded bytecode or from the execution itself. Only available in debug builds, so a
+    /// release binary can't be misconfigured into it.
+    #[cfg(any(test, debug_assertions))]
+    pub const fn with_skip_system_contract_upgrades(mut self, skip: bool) -> Self {
+        self.skip_system_contract_upgrades = skip;
+        self
+    }
+
     /// Applies system contract upgrades if the Feynman fork is not yet active.
     fn upgrade_contracts(&mut self) -> Result<(), BlockExecutionError> {
+        #[cfg(any(test, debug_assertions))]
+        if self.skip_system_contract_upgrades {
+            warn!(
+                target: "evm",
+                number = self.evm.block().number.to::<u64>(),
+                "Skipping system contract upgrades"
+            );
+            return Ok(());
+        }
+
         let contracts = get_upgrade_system_contracts(
             &self.spec,
             self.evm.block().number.to(),
@@ -211,11 +284,10 @@ where
             hook.on_state(StateChangeSource::Transaction(self.receipts.len()), &state);
         } 
 
-        let tx = tx.clone();
         let gas_used = result.gas_used();
         self.gas_used += gas_used;
         self.receipts.push(self.receipt_builder.build_receipt(ReceiptBuilderCtx {
-            tx: &tx,
+            tx,
             evm: &self.evm,
             result,
             state: &state,
@@ -246,16 +318,7 @@ where
 
     /// Handle slash system tx
     fn handle_slash_tx(&mut self, tx: &TransactionSigned) -> Result<(), BlockExecutionError> {
-        sol!(
-            function slash(
-                address amounts,
-            );
-        );
-
-        let input = tx.input();
-        let is_slash_tx = input.len() >= 4 && input[..4] == slashCall::SELECTOR;
-
-        if is_slash_tx {
+        if SystemCallKind::from_input(tx.input()) == Some(SystemCallKind::Slash) {
             let signer = tx.recover_signer().map_err(BlockExecutionError::other)?;
             self.transact_system_tx(tx, signer)?;
         }
@@ -270,18 +333,7 @@ where
         &mut self,
         tx: &TransactionSigned,
     ) -> Result<(), BlockExecutionError> {
-        sol!(
-            function distributeFinalityReward(
-                address[] validators,
-                uint256[] weights
-            );
-        );
-
-        let input = tx.input();
-        let is_finality_reward_tx =
-            input.len() >= 4 && input[..4] == distributeFinalityRewardCall::SELECTOR;
-
-        if is_finality_reward_tx {
+        if SystemCallKind::from_input(tx.input()) == Some(SystemCallKind::DistributeFinalityReward) {
             let signer = tx.recover_signer().map_err(BlockExecutionError::other)?;
             self.transact_system_tx(tx, signer)?;
         }
@@ -295,19 +347,7 @@ where
         &mut self,
         tx: &TransactionSigned,
     ) -> Result<(), BlockExecutionError> {
-        sol!(
-            function updateValidatorSetV2(
-                address[] _consensusAddrs,
-                uint64[] _votingPowers,
-                bytes[] _voteAddrs
-            );
-        );
-
-        let input = tx.input();
-        let is_update_validator_set_v2_tx =
-            input.len() >= 4 && input[..4] == updateValidatorSetV2Call::SELECTOR;
-
-        if is_update_validator_set_v2_tx {
+        if SystemCallKind::from_input(tx.input()) == Some(SystemCallKind::UpdateValidatorSetV2) {
             let signer = tx.recover_signer().map_err(BlockExecutionError::other)?;
             self.transact_system_tx(tx, signer)?;
         }
@@ -370,17 +410,25 @@ where
         &mut self,
         block_number: BlockNumber,
     ) -> Result<bool, BlockExecutionError> {
+        let account = self.evm.db_mut().load_cache_account(HISTORY_STORAGE_ADDRESS).map_err(|err| {
+            BlockExecutionError::other(err)
+        })?;
+
+        // The parent timestamp isn't part of the execution context, so rather than relying on an
+        // exact transition check the deployment is skipped once the contract is in place.
+        let code_hash = keccak256(HISTORY_STORAGE_CODE.clone());
+        if account.account_info().is_some_and(|info| info.code_hash == code_hash && info.nonce == 1)
+        {
+            return Ok(false);
+        }
+
         debug!(
             "Apply history storage account {:?} at height {:?}",
             HISTORY_STORAGE_ADDRESS, block_number
         );
 
-        let account = self.evm.db_mut().load_cache_account(HISTORY_STORAGE_ADDRESS).map_err(|err| {
-            BlockExecutionError::other(err)
-        })?;
-
         let mut new_info = account.account_info().unwrap_or_default();
-        new_info.code_hash = keccak256(HISTORY_STORAGE_CODE.clone());
+        new_info.code_hash = code_hash;
         new_info.code = Some(Bytecode::new_raw(Bytes::from_static(&HISTORY_STORAGE_CODE)));
         new_info.nonce = 1_u64;
         new_info.balance = U256::ZERO;
@@ -424,11 +472,10 @@ where
             self.upgrade_contracts()?;
         }
 
-        // enable BEP-440/EIP-2935 for historical block hashes from state
-        if self.spec.is_prague_transition_at_timestamp(self.evm.block().timestamp.to(), self.evm.block().timestamp.to::<u64>() - 3) {
-            self.apply_history_storage_account(self.evm.block().number.to::<u64>())?;
-        }
+        // enable BEP-440/EIP-2935 for historical block hashes from state, the history storage
+        // account is deployed by the first Pascal block
         if self.spec.is_prague_active_at_timestamp(self.evm.block().timestamp.to()) {
+            self.apply_history_storage_account(self.evm.block().number.to::<u64>())?;
             self.system_caller.apply_blockhashes_contract_call(self._ctx.parent_hash, &mut self.evm)?;
         }
 
@@ -456,10 +503,22 @@ where
             self.system_txs.push(tx.tx().clone());
             return Ok(0);
         }
+        if !self.system_txs.is_empty() &&
+            BscHardforks::is_cancun_active_at_timestamp(
+                &self.spec,
+                self.evm.block().timestamp.to(),
+            )
+        {
+            let hash = *tx.tx().tx_hash();
+            return Err(BscBlockExecutionError::SystemTxNotAtEnd { hash }.into());
+        }
 
         // apply patches before
-        patch_mainnet_before_tx(tx.tx(), self.evm.db_mut())?;
-        patch_chapel_before_tx(tx.tx(), self.evm.db_mut())?;
+        let hertz_patches = is_hertz_patch_block(&self.spec, self.evm.block().number.to());
+        if hertz_patches {
+            patch_mainnet_before_tx(tx.tx(), self.evm.db_mut())?;
+            patch_chapel_before_tx(tx.tx(), self.evm.db_mut())?;
+        }
 
         let block_available_gas = self.evm.block().gas_limit - self.gas_used;
         if tx.tx().gas_limit() > block_available_gas {
@@ -496,8 +555,10 @@ where
         self.evm.db_mut().commit(state);
 
         // apply patches after
-        patch_mainnet_after_tx(tx.tx(), self.evm.db_mut())?;
-        patch_chapel_after_tx(tx.tx(), self.evm.db_mut())?;
+        if hertz_patches {
+            patch_mainnet_after_tx(tx.tx(), self.evm.db_mut())?;
+            patch_chapel_after_tx(tx.tx(), self.evm.db_mut())?;
+        }
 
         Ok(gas_used)
     }
@@ -510,7 +571,7 @@ where
         // Consensus: Verify turn length
 
         // If first block deploy genesis contracts
-        if self.evm.block().number == uint!(1U256) {
+        if BscChainSpec::is_first_block(self.evm.block().number.to()) {
             self.deploy_genesis_contracts(self.evm.block().beneficiary)?;
         }
 
@@ -526,7 +587,8 @@ where
             self.initialize_feynman_contracts(self.evm.block().beneficiary)?;
         }
 
-        let system_txs = self.system_txs.clone();
+        // The system transactions are only needed here, move them out instead of cloning.
+        let system_txs = std::mem::take(&mut self.system_txs);
         for tx in &system_txs {
             self.handle_slash_tx(tx)?;
         }
@@ -534,14 +596,13 @@ where
         self.distribute_block_rewards(self.evm.block().beneficiary)?;
 
         if self.spec.is_plato_active_at_block(self.evm.block().number.to()) {
-            for tx in system_txs {
-                self.handle_finality_reward_tx(&tx)?;
+            for tx in &system_txs {
+                self.handle_finality_reward_tx(tx)?;
             }
         }
 
         // TODO: add breathe check and polish it later.
-        let system_txs_v2 = self.system_txs.clone();
-        for tx in &system_txs_v2 {
+        for tx in &system_txs {
             self.handle_update_validator_set_v2_tx(tx)?;
         }
 
@@ -570,3 +631,374 @@ where
         &self.evm
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bsc_system::{
+            SLASH_CONTRACT, STAKING_CONTRACT, VALIDATOR_CONTRACT,
+        },
+        chainspec::bsc::bsc_mainnet,
+        evm::api::BscEvm,
+        node::evm::config::BscEvmConfig,
+    };
+    use alloy_consensus::{constants::ETH_TO_WEI, Header, TxLegacy};
+    use alloy_eips::eip2935::HISTORY_SERVE_WINDOW;
+    use alloy_primitives::{address, Signature, B256, KECCAK_EMPTY};
+    use reth_evm::{ConfigureEvm, EvmFactory};
+    use reth_evm_ethereum::RethReceiptBuilder;
+    use reth_primitives::Recovered;
+    use revm::{
+        database::{states::bundle_state::BundleRetention, EmptyDB},
+        inspector::NoOpInspector,
+        state::AccountInfo,
+        Database as _,
+    };
+    use std::sync::Arc;
+
+    type TestExecutor<'a> = BscBlockExecutor<
+        'a,
+        BscEvm<&'a mut State<EmptyDB>, NoOpInspector>,
+        Arc<BscChainSpec>,
+        RethReceiptBuilder,
+    >;
+
+    fn mainnet() -> Arc<BscChainSpec> {
+        Arc::new(BscChainSpec::from(bsc_mainnet()))
+    }
+
+    fn new_state() -> State<EmptyDB> {
+        State::builder().with_database(EmptyDB::default()).with_bundle_update().build()
+    }
+
+    fn executor<'a>(
+        chain_spec: &Arc<BscChainSpec>,
+        state: &'a mut State<EmptyDB>,
+        number: u64,
+        timestamp: u64,
+    ) -> TestExecutor<'a> {
+        let config = BscEvmConfig::new(chain_spec.clone());
+        let header = Header {
+            number,
+            timestamp,
+            gas_limit: 140_000_000,
+            base_fee_per_gas: Some(0),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let evm = config.evm_factory().create_evm(state, config.evm_env(&header));
+        let ctx = EthBlockExecutionCtx {
+            parent_hash: B256::with_last_byte(number as u8),
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: None,
+        };
+        BscBlockExecutor::new(
+            evm,
+            ctx,
+            chain_spec.clone(),
+            RethReceiptBuilder::default(),
+            SystemContract::new(chain_spec.clone()),
+        )
+    }
+
+    /// Mainnet Feynman activation, 2024-04-18 05:49:00 AM UTC.
+    const FEYNMAN: u64 = 1_713_419_340;
+
+    /// Executes a block without transactions, returns the number of executed system txs.
+    fn execute_empty_block(
+        chain_spec: &Arc<BscChainSpec>,
+        state: &mut State<EmptyDB>,
+        timestamp: u64,
+    ) -> usize {
+        // past all block based forks
+        let mut executor = executor(chain_spec, state, 38_000_000, timestamp);
+        executor.apply_pre_execution_changes().unwrap();
+        let (_, result) = executor.finish().unwrap();
+        result.receipts.len()
+    }
+
+    fn assert_history_storage_account(state: &mut State<EmptyDB>) {
+        let info = state.basic(HISTORY_STORAGE_ADDRESS).unwrap().unwrap();
+        assert_eq!(info.code_hash, keccak256(HISTORY_STORAGE_CODE.clone()));
+        assert_eq!(info.nonce, 1);
+        assert_eq!(info.balance, U256::ZERO);
+    }
+
+    #[test]
+    fn test_verify_no_duplicate_transactions() {
+        let tx = |nonce| {
+            TransactionSigned::new_unhashed(
+                TxLegacy { chain_id: Some(56), nonce, gas_limit: 21_000, ..Default::default() }
+                    .into(),
+                Signature::new(U256::from(1), U256::from(1), false),
+            )
+        };
+        let (first, second) = (tx(0), tx(1));
+
+        assert!(verify_no_duplicate_transactions(&[]).is_ok());
+        assert!(verify_no_duplicate_transactions(&[first.clone(), second.clone()]).is_ok());
+        assert_eq!(
+            verify_no_duplicate_transactions(&[first.clone(), second, first.clone()]),
+            Err(BscBlockExecutionError::DuplicateTransaction { hash: *first.tx_hash() })
+        );
+    }
+
+    #[test]
+    fn test_system_txs_at_end() {
+        let tx = |to, gas_price| {
+            TransactionSigned::new_unhashed(
+                TxLegacy {
+                    chain_id: Some(56),
+                    gas_price,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(to),
+                    ..Default::default()
+                }
+                .into(),
+                Signature::test_signature(),
+            )
+        };
+        let system = tx(SLASH_CONTRACT, 0);
+        let user = tx(Address::repeat_byte(0x11), 1_000_000_000);
+        let coinbase = system.recover_signer().unwrap();
+
+        assert!(verify_system_txs_at_end(&[user.clone(), system.clone()], coinbase).is_ok());
+        assert_eq!(
+            verify_system_txs_at_end(&[system.clone(), user.clone(), system.clone()], coinbase),
+            Err(BscBlockExecutionError::SystemTxNotAtEnd { hash: *user.tx_hash() })
+        );
+        // not a system transaction unless sent by the block producer
+        assert!(verify_system_txs_at_end(&[system.clone(), user.clone()], Address::ZERO).is_ok());
+
+        // since Cancun, the executor rejects the user transaction before executing it
+        let chain_spec = mainnet();
+        let execute = |number, timestamp, user: &TransactionSigned| {
+            let mut state = new_state();
+            let mut executor = executor(&chain_spec, &mut state, number, timestamp);
+            executor.system_txs.push(system.clone());
+            let result =
+                executor.execute_transaction(&Recovered::new_unchecked(user.clone(), coinbase));
+            (result, executor.receipts.len())
+        };
+        let (result, receipts) = execute(40_000_000, 1_720_000_000, &user);
+        assert!(result.unwrap_err().to_string().contains(&user.tx_hash().to_string()));
+        assert_eq!(receipts, 0);
+
+        // before Cancun, interleaved blocks are valid
+        let user = tx(Address::repeat_byte(0x11), 0);
+        let (result, receipts) = execute(32_000_000, 1_690_000_000, &user);
+        assert!(result.is_ok());
+        assert_eq!(receipts, 1);
+    }
+
+    #[test]
+    fn test_skip_system_contract_upgrades() {
+        let chain_spec = mainnet();
+        // Gibbs upgrades the staking contract
+        let gibbs = 23_846_001;
+        let timestamp = 1_670_000_000;
+        let header = Header { number: gibbs, timestamp, ..Default::default() };
+        let ctx = EthBlockExecutionCtx {
+            parent_hash: B256::ZERO,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: None,
+        };
+
+        // the switch is set on the node's EVM config, which hands it to every executor
+        let staking_contract = |skip: bool| {
+            let config =
+                BscEvmConfig::new(chain_spec.clone()).with_skip_system_contract_upgrades(skip);
+            let mut state = new_state();
+            {
+                let evm = config.evm_factory().create_evm(&mut state, config.evm_env(&header));
+                let mut executor = config.create_executor(evm, ctx.clone());
+                executor.apply_pre_execution_changes().unwrap();
+            }
+            state.basic(STAKING_CONTRACT).unwrap()
+        };
+
+        assert!(staking_contract(true).is_none());
+        assert_ne!(staking_contract(false).unwrap().code_hash, KECCAK_EMPTY);
+    }
+
+    #[test]
+    fn test_history_storage_account_applied_once() {
+        let chain_spec = mainnet();
+        let pascal = 1742436600;
+        let number = 47_618_307;
+
+        // 3s interval: the following block is well after the transition,
+        // 0.75s interval: the following block shares the transition's second.
+        for next_timestamp in [pascal + 3, pascal] {
+            let mut state = new_state();
+
+            // last block before Pascal doesn't deploy the contract
+            {
+                let mut pre = executor(&chain_spec, &mut state, number - 1, pascal - 1);
+                pre.apply_pre_execution_changes().unwrap();
+            }
+            assert!(state.basic(HISTORY_STORAGE_ADDRESS).unwrap().is_none());
+
+            // the transition block deploys it
+            {
+                let mut transition = executor(&chain_spec, &mut state, number, pascal);
+                transition.apply_pre_execution_changes().unwrap();
+            }
+            assert_history_storage_account(&mut state);
+
+            // the following block leaves it untouched
+            {
+                let mut next = executor(&chain_spec, &mut state, number + 1, next_timestamp);
+                assert!(!next.apply_history_storage_account(number + 1).unwrap());
+                next.apply_pre_execution_changes().unwrap();
+            }
+            assert_history_storage_account(&mut state);
+        }
+    }
+
+    #[test]
+    fn test_history_storage_ring_buffer() {
+        let chain_spec = mainnet();
+        let pascal = 1_742_436_600;
+        let number = 47_618_307;
+        let window = HISTORY_SERVE_WINDOW as u64;
+
+        // the contract stores the parent hash of block n at slot (n - 1) % 8191, bsc-geth deploys
+        // the same bytecode and uses the same window
+        let mut state = new_state();
+        let blocks = [number, number + 1, number + 2, number + window];
+        for (i, block) in blocks.into_iter().enumerate() {
+            {
+                let mut executor = executor(&chain_spec, &mut state, block, pascal + i as u64);
+                executor.apply_pre_execution_changes().unwrap();
+            }
+            let slot = U256::from((block - 1) % window);
+            let stored = state.storage(HISTORY_STORAGE_ADDRESS, slot).unwrap();
+            assert_eq!(B256::from(stored), B256::with_last_byte(block as u8));
+        }
+
+        // the last block wrapped around and overwrote the slot of the first one
+        let first_slot = U256::from((number - 1) % window);
+        let stored = state.storage(HISTORY_STORAGE_ADDRESS, first_slot).unwrap();
+        assert_eq!(B256::from(stored), B256::with_last_byte((number + window) as u8));
+        assert_ne!(B256::from(stored), B256::with_last_byte(number as u8));
+    }
+
+    #[test]
+    fn test_pre_execution_is_deterministic() {
+        let chain_spec = mainnet();
+        // Gibbs upgrades system contracts, Pascal deploys the history storage account
+        let blocks = [(23_846_001, 1_670_000_000), (47_618_307, 1_742_436_600)];
+
+        let execute = |number, timestamp| {
+            let mut state = new_state();
+            {
+                let mut executor = executor(&chain_spec, &mut state, number, timestamp);
+                executor.apply_pre_execution_changes().unwrap();
+            }
+            state.merge_transitions(BundleRetention::Reverts);
+            state.take_bundle()
+        };
+
+        // re-executing a block after an unwind starts from the same parent state and must
+        // produce the same changes and reverts
+        for (number, timestamp) in blocks {
+            let bundle = execute(number, timestamp);
+            assert!(!bundle.is_empty());
+            assert_eq!(bundle, execute(number, timestamp));
+        }
+    }
+
+    #[test]
+    fn test_block_before_feynman_transition() {
+        let chain_spec = mainnet();
+        let mut state = new_state();
+        assert!(!chain_spec.is_feynman_active_at_timestamp(FEYNMAN - 3));
+
+        // the feynman contracts are neither deployed nor initialized yet
+        assert_eq!(execute_empty_block(&chain_spec, &mut state, FEYNMAN - 3), 0);
+        assert!(state.basic(STAKE_HUB_CONTRACT).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_feynman_transition_block() {
+        let chain_spec = mainnet();
+        let mut state = new_state();
+        assert!(chain_spec.is_feynman_transition_at_timestamp(FEYNMAN, FEYNMAN - 3));
+
+        // the transition block deploys and initializes the feynman contracts
+        let init_txs = SystemContract::new(chain_spec.clone()).feynman_contracts_txs().len();
+        assert_eq!(execute_empty_block(&chain_spec, &mut state, FEYNMAN), init_txs);
+        let info = state.basic(STAKE_HUB_CONTRACT).unwrap().unwrap();
+        assert_ne!(info.code_hash, KECCAK_EMPTY);
+    }
+
+    #[test]
+    fn test_block_after_feynman_transition() {
+        let chain_spec = mainnet();
+        assert!(chain_spec.is_feynman_active_at_timestamp(FEYNMAN + 3));
+        assert!(!chain_spec.is_feynman_transition_at_timestamp(FEYNMAN + 3, FEYNMAN));
+
+        // the contracts initialized by the transition block aren't initialized again
+        let mut state = new_state();
+        state.insert_account_with_storage(
+            STAKE_HUB_CONTRACT,
+            Default::default(),
+            [(U256::ZERO, U256::from(1))].into_iter().collect(),
+        );
+        assert_eq!(execute_empty_block(&chain_spec, &mut state, FEYNMAN + 3), 0);
+
+        // nor are they once the transition window has passed
+        let mut state = new_state();
+        assert_eq!(execute_empty_block(&chain_spec, &mut state, FEYNMAN + 101), 0);
+    }
+
+    #[test]
+    fn test_block_reward_routing_after_bruno() {
+        let chain_spec = mainnet();
+        let bruno = 13_082_000;
+        let (before_kepler, after_kepler) = (1_638_300_000, 1_705_996_800);
+        assert!(chain_spec.is_bruno_active_at_block(bruno));
+        assert!(!chain_spec.is_kepler_active_at_timestamp(before_kepler));
+
+        let validator = address!("0x72b61c6014342d914470ec7ac2975be345796c2b");
+        let fees = 16 * ETH_TO_WEI;
+        let distribute = |timestamp, system_reward_balance: u128| {
+            let mut state = new_state();
+            state.insert_account(
+                SYSTEM_ADDRESS,
+                AccountInfo { balance: U256::from(fees), ..Default::default() },
+            );
+            state.insert_account(
+                SYSTEM_REWARD_CONTRACT,
+                AccountInfo { balance: U256::from(system_reward_balance), ..Default::default() },
+            );
+            {
+                let mut executor = executor(&chain_spec, &mut state, bruno + 1, timestamp);
+                executor.distribute_block_rewards(validator).unwrap();
+            }
+            let mut balance = |address| state.basic(address).unwrap().unwrap_or_default().balance;
+            assert_eq!(balance(SYSTEM_ADDRESS), U256::ZERO);
+            assert_eq!(balance(validator), U256::ZERO);
+            (
+                balance(SYSTEM_REWARD_CONTRACT) - U256::from(system_reward_balance),
+                balance(VALIDATOR_CONTRACT),
+            )
+        };
+
+        // a sixteenth of the fees goes to the system reward contract, the rest is deposited
+        let system_reward = fees >> SYSTEM_REWARD_PERCENT;
+        assert_eq!(
+            distribute(before_kepler, 0),
+            (U256::from(system_reward), U256::from(fees - system_reward))
+        );
+        // unless the system reward contract is full
+        assert_eq!(distribute(before_kepler, MAX_SYSTEM_REWARD), (U256::ZERO, U256::from(fees)));
+        // Kepler stopped paying the system reward contract
+        assert_eq!(distribute(after_kepler, 0), (U256::ZERO, U256::from(fees)));
+    }
+}
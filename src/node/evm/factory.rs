@@ -15,7 +15,17 @@ use revm::{
 /// Factory producing [`BscEvm`].
 #[derive(Debug, Default, Clone, Copy)]
 #[non_exhaustive]
-pub struct BscEvmFactory;
+pub struct BscEvmFactory {
+    /// Chain id of the chain spec the environments are built from, unchecked if unknown.
+    chain_id: Option<u64>,
+}
+
+impl BscEvmFactory {
+    /// Creates a factory for EVMs whose environments are built for the given chain.
+    pub const fn new(chain_id: u64) -> Self {
+        Self { chain_id: Some(chain_id) }
+    }
+}
 
 impl EvmFactory for BscEvmFactory {
     type Evm<DB: Database, I: Inspector<BscContext<DB>>> = BscEvm<DB, I>;
@@ -31,7 +41,7 @@ impl EvmFactory for BscEvmFactory {
         db: DB,
         input: EvmEnv<BscHardfork>,
     ) -> Self::Evm<DB, NoOpInspector> {
-        BscEvm::new(input, db, NoOpInspector {}, false)
+        BscEvm::new(input, db, NoOpInspector {}, false).with_expected_chain_id(self.chain_id)
     }
 
     fn create_evm_with_inspector<DB: Database, I: Inspector<Self::Context<DB>>>(
@@ -40,6 +50,6 @@ impl EvmFactory for BscEvmFactory {
         input: EvmEnv<BscHardfork>,
         inspector: I,
     ) -> Self::Evm<DB, I> {
-        BscEvm::new(input, db, inspector, true)
+        BscEvm::new(input, db, inspector, true).with_expected_chain_id(self.chain_id)
     }
 }
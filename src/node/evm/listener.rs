@@ -0,0 +1,497 @@
+//! Hook into the system transactions of the canonical chain.
+//!
+//! Slashes, finality reward distributions and validator set updates are decoded from the blocks
+//! becoming canonical, a listener receives them without having to re-index the chain. Blocks
+//! executed on a side chain or executed again are not reported.
+
+use crate::{
+    bsc_system::SystemCall, node::primitives::BscPrimitives,
+    system_contracts::is_system_transaction, BscBlock,
+};
+use alloy_consensus::Transaction;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, BlockNumber, B256, U256};
+use futures::{Stream, StreamExt};
+use reth_primitives::RecoveredBlock;
+use reth_provider::CanonStateNotification;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Arc,
+    },
+    thread,
+};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// A system transaction included in a canonical block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemTxEvent {
+    /// The including block.
+    pub block: BlockNumHash,
+    /// Sender of the system transaction, i.e. the validator of the block.
+    pub caller: Address,
+    /// The decoded call.
+    pub call: SystemCall,
+}
+
+/// Receives the system transactions of the blocks becoming canonical.
+///
+/// The listener is called in the order of the canonical chain. Blocks reorged out of the chain
+/// are not retracted, the block hash tells the events of competing blocks apart. All methods
+/// default to doing nothing, implementations pick the events they are interested in.
+pub trait BscExecutionListener: fmt::Debug + Send + Sync {
+    /// Called for every slash, finality reward and validator set update transaction.
+    fn on_system_tx(&self, _event: &SystemTxEvent) {}
+
+    /// Called when the validator `caller` slashed the `offender` for missing its turn.
+    fn on_slash(&self, _block: BlockNumHash, _offender: Address, _caller: Address) {}
+
+    /// Called when a validator set update changed the validators.
+    ///
+    /// The previous set is the one of the last update seen, `None` for the first update after
+    /// the node started.
+    fn on_validator_set_change(
+        &self,
+        _block: BlockNumHash,
+        _old: Option<&[Address]>,
+        _new: &[Address],
+    ) {
+    }
+
+    /// Called when the finality rewards were distributed, with the weight of every validator.
+    fn on_finality_reward(&self, _block: BlockNumHash, _distribution: &[(Address, U256)]) {}
+}
+
+/// Feeds the listeners with the system transactions of the blocks becoming canonical, until the
+/// notification stream ends.
+pub async fn notify_canonical_blocks<St>(
+    listeners: Vec<Arc<dyn BscExecutionListener>>,
+    mut stream: St,
+) where
+    St: Stream<Item = CanonStateNotification<BscPrimitives>> + Unpin,
+{
+    let mut dispatcher = EventDispatcher::new(listeners);
+    while let Some(notification) = stream.next().await {
+        for block in notification.committed().blocks_iter() {
+            dispatcher.on_block(block);
+        }
+    }
+}
+
+/// Decodes the system transactions of canonical blocks and calls every listener in turn.
+#[derive(Debug)]
+struct EventDispatcher {
+    listeners: Vec<Arc<dyn BscExecutionListener>>,
+    /// Validators of the last validator set update.
+    validators: Option<Vec<Address>>,
+}
+
+impl EventDispatcher {
+    fn new(listeners: Vec<Arc<dyn BscExecutionListener>>) -> Self {
+        Self { listeners, validators: None }
+    }
+
+    fn on_block(&mut self, block: &RecoveredBlock<BscBlock>) {
+        let at = BlockNumHash::new(block.header().number, block.hash());
+        let coinbase = block.header().beneficiary;
+        for (caller, tx) in block.transactions_with_sender() {
+            // user transactions calling the system contracts are no system transactions
+            if !is_system_transaction(tx, *caller, coinbase) {
+                continue;
+            }
+            if let Some(call) = SystemCall::decode(tx.input()) {
+                self.on_system_call(at, *caller, call);
+            }
+        }
+    }
+
+    fn on_system_call(&mut self, block: BlockNumHash, caller: Address, call: SystemCall) {
+        match &call {
+            SystemCall::Slash(slash) => {
+                for listener in &self.listeners {
+                    listener.on_slash(block, slash.validator, caller);
+                }
+            }
+            SystemCall::DistributeFinalityReward(reward) => {
+                let distribution: Vec<_> =
+                    reward.validators.iter().copied().zip(reward.weights.iter().copied()).collect();
+                for listener in &self.listeners {
+                    listener.on_finality_reward(block, &distribution);
+                }
+            }
+            SystemCall::UpdateValidatorSetV2(update) => {
+                let new = &update._consensusAddrs;
+                if self.validators.as_ref() != Some(new) {
+                    for listener in &self.listeners {
+                        listener.on_validator_set_change(block, self.validators.as_deref(), new);
+                    }
+                    self.validators = Some(new.clone());
+                }
+            }
+            // block rewards are paid in every block
+            SystemCall::Deposit(_) => return,
+        }
+        let event = SystemTxEvent { block, caller, call };
+        for listener in &self.listeners {
+            listener.on_system_tx(&event);
+        }
+    }
+}
+
+/// Listener appending the events as JSON lines to a file.
+///
+/// Events are handed to a background writer over a bounded channel. If the writer falls behind,
+/// events are dropped and counted instead of stalling the notifications.
+#[derive(Debug)]
+pub struct JsonLinesListener {
+    events: SyncSender<serde_json::Value>,
+    dropped: AtomicU64,
+}
+
+impl JsonLinesListener {
+    /// Default number of events buffered for the writer.
+    pub const DEFAULT_CAPACITY: usize = 1_024;
+
+    /// Opens the file in append mode and spawns the writer thread.
+    pub fn spawn(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (events, rx) = mpsc::sync_channel(capacity);
+        thread::Builder::new()
+            .name("bsc-execution-events".to_string())
+            .spawn(move || write_events(BufWriter::new(file), rx))?;
+        Ok(Self { events, dropped: AtomicU64::new(0) })
+    }
+
+    /// Returns the number of events dropped because the writer fell behind or stopped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn send(&self, event: serde_json::Value) {
+        if self.events.try_send(event).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl BscExecutionListener for JsonLinesListener {
+    fn on_slash(&self, block: BlockNumHash, offender: Address, caller: Address) {
+        self.send(json!({
+            "event": "slash",
+            "block": block.number,
+            "hash": block.hash,
+            "caller": caller,
+            "offender": offender,
+        }));
+    }
+
+    fn on_validator_set_change(
+        &self,
+        block: BlockNumHash,
+        old: Option<&[Address]>,
+        new: &[Address],
+    ) {
+        self.send(json!({
+            "event": "validatorSetChange",
+            "block": block.number,
+            "hash": block.hash,
+            "previous": old,
+            "validators": new,
+        }));
+    }
+
+    fn on_finality_reward(&self, block: BlockNumHash, distribution: &[(Address, U256)]) {
+        let (validators, weights): (Vec<_>, Vec<_>) = distribution.iter().copied().unzip();
+        self.send(json!({
+            "event": "finalityReward",
+            "block": block.number,
+            "hash": block.hash,
+            "validators": validators,
+            "weights": weights,
+        }));
+    }
+}
+
+/// A validator slashed for missing its turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashEvent {
+    /// Number of the block including the slash.
+    pub block: BlockNumber,
+    /// Hash of the block including the slash.
+    pub hash: B256,
+    /// The slashed validator.
+    pub validator: Address,
+    /// Validator of the block that included the slash.
+    pub beneficiary: Address,
+}
+
+/// Listener publishing the slashes on a broadcast channel.
+///
+/// Subscribers that fall behind miss the oldest events, see [`broadcast::Receiver::recv`].
+#[derive(Debug, Clone)]
+pub struct SlashingListener {
+    events: broadcast::Sender<SlashEvent>,
+}
+
+impl SlashingListener {
+    /// Default number of events buffered per subscriber.
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// Creates a new listener buffering up to `capacity` events per subscriber.
+    pub fn new(capacity: usize) -> Self {
+        Self { events: broadcast::channel(capacity).0 }
+    }
+
+    /// Returns a receiver for all slashes from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<SlashEvent> {
+        self.events.subscribe()
+    }
+}
+
+impl Default for SlashingListener {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl BscExecutionListener for SlashingListener {
+    fn on_slash(&self, block: BlockNumHash, offender: Address, caller: Address) {
+        // no subscribers is not an error
+        let _ = self.events.send(SlashEvent {
+            block: block.number,
+            hash: block.hash,
+            validator: offender,
+            beneficiary: caller,
+        });
+    }
+}
+
+/// Writes the received events until the listener is dropped.
+fn write_events(mut writer: impl Write, events: Receiver<serde_json::Value>) {
+    for event in events {
+        if let Err(err) = writeln!(writer, "{event}").and_then(|_| writer.flush()) {
+            warn!(target: "evm", %err, "Stopped writing execution events");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bsc_system::{
+            depositCall, slashCall, updateValidatorSetV2Call, SLASH_CONTRACT, VALIDATOR_CONTRACT,
+        },
+        BscBlockBody,
+    };
+    use alloy_consensus::{BlockBody, Header, TxLegacy};
+    use alloy_primitives::{address, Bytes, Signature, TxKind};
+    use alloy_sol_types::SolCall;
+    use parking_lot::Mutex;
+    use reth_primitives::TransactionSigned;
+
+    /// Every call received by a listener.
+    #[derive(Debug, PartialEq, Eq)]
+    enum Received {
+        SystemTx(SystemTxEvent),
+        Slash(BlockNumHash, Address, Address),
+        ValidatorSetChange(BlockNumHash, Option<Vec<Address>>, Vec<Address>),
+        FinalityReward(BlockNumHash, Vec<(Address, U256)>),
+    }
+
+    /// Listener collecting all events.
+    #[derive(Debug, Default)]
+    struct CollectingListener(Mutex<Vec<Received>>);
+
+    impl BscExecutionListener for CollectingListener {
+        fn on_system_tx(&self, event: &SystemTxEvent) {
+            self.0.lock().push(Received::SystemTx(event.clone()));
+        }
+
+        fn on_slash(&self, block: BlockNumHash, offender: Address, caller: Address) {
+            self.0.lock().push(Received::Slash(block, offender, caller));
+        }
+
+        fn on_validator_set_change(
+            &self,
+            block: BlockNumHash,
+            old: Option<&[Address]>,
+            new: &[Address],
+        ) {
+            let old = old.map(<[Address]>::to_vec);
+            self.0.lock().push(Received::ValidatorSetChange(block, old, new.to_vec()));
+        }
+
+        fn on_finality_reward(&self, block: BlockNumHash, distribution: &[(Address, U256)]) {
+            self.0.lock().push(Received::FinalityReward(block, distribution.to_vec()));
+        }
+    }
+
+    /// Builds a transaction without gas price, as system transactions are.
+    fn tx(to: Address, input: Vec<u8>) -> TransactionSigned {
+        let tx = TxLegacy {
+            chain_id: Some(56),
+            to: TxKind::Call(to),
+            gas_limit: u64::MAX / 2,
+            input: input.into(),
+            ..Default::default()
+        };
+        TransactionSigned::new_unhashed(tx.into(), Signature::test_signature())
+    }
+
+    /// Builds a block of the given validator with the given transactions and senders.
+    fn block(
+        number: u64,
+        coinbase: Address,
+        txs: Vec<(Address, TransactionSigned)>,
+    ) -> RecoveredBlock<BscBlock> {
+        let (senders, transactions) = txs.into_iter().unzip();
+        let block = BscBlock {
+            header: Header { number, beneficiary: coinbase, ..Default::default() },
+            body: BscBlockBody {
+                inner: BlockBody { transactions, ommers: Vec::new(), withdrawals: None },
+                sidecars: None,
+            },
+        };
+        RecoveredBlock::new_unhashed(block, senders)
+    }
+
+    fn update(validators: Vec<Address>) -> Vec<u8> {
+        updateValidatorSetV2Call {
+            _votingPowers: vec![1; validators.len()],
+            _voteAddrs: vec![Bytes::new(); validators.len()],
+            _consensusAddrs: validators,
+        }
+        .abi_encode()
+    }
+
+    #[test]
+    fn test_canonical_events() {
+        let coinbase = address!("0xe2d3a739effcd3a99387d015e260eefac72ebea1");
+        let offender = address!("0x72b61c6014342d914470ec7ac2975be345796c2b");
+        let user = Address::repeat_byte(0x11);
+        let slash = slashCall { validator: offender };
+        let deposit = depositCall { valAddr: coinbase }.abi_encode();
+
+        let blocks = [
+            // a user calling the slash contract is ignored, as is the block reward
+            block(
+                1,
+                coinbase,
+                vec![
+                    (user, tx(SLASH_CONTRACT, slash.abi_encode())),
+                    (coinbase, tx(SLASH_CONTRACT, slash.abi_encode())),
+                    (coinbase, tx(VALIDATOR_CONTRACT, deposit)),
+                    (coinbase, tx(VALIDATOR_CONTRACT, update(vec![coinbase, offender]))),
+                ],
+            ),
+            // an update keeping the validators is no change
+            block(2, coinbase, vec![(coinbase, tx(VALIDATOR_CONTRACT, update(vec![coinbase])))]),
+            block(3, coinbase, vec![(coinbase, tx(VALIDATOR_CONTRACT, update(vec![coinbase])))]),
+        ];
+        let at = |block: &RecoveredBlock<BscBlock>| {
+            BlockNumHash::new(block.header().number, block.hash())
+        };
+
+        // every listener receives all events
+        let (listener, other) =
+            (Arc::new(CollectingListener::default()), Arc::new(CollectingListener::default()));
+        let mut dispatcher = EventDispatcher::new(vec![
+            listener.clone() as Arc<dyn BscExecutionListener>,
+            other.clone(),
+        ]);
+        for block in &blocks {
+            dispatcher.on_block(block);
+        }
+        assert_eq!(*listener.0.lock(), *other.0.lock());
+
+        let system_tx =
+            |block, call| Received::SystemTx(SystemTxEvent { block, caller: coinbase, call });
+        let full_update = |validators: Vec<Address>| {
+            let call = updateValidatorSetV2Call::abi_decode(&update(validators)).unwrap();
+            SystemCall::UpdateValidatorSetV2(call)
+        };
+        assert_eq!(
+            *listener.0.lock(),
+            vec![
+                Received::Slash(at(&blocks[0]), offender, coinbase),
+                system_tx(at(&blocks[0]), SystemCall::Slash(slash)),
+                Received::ValidatorSetChange(at(&blocks[0]), None, vec![coinbase, offender]),
+                system_tx(at(&blocks[0]), full_update(vec![coinbase, offender])),
+                Received::ValidatorSetChange(
+                    at(&blocks[1]),
+                    Some(vec![coinbase, offender]),
+                    vec![coinbase]
+                ),
+                system_tx(at(&blocks[1]), full_update(vec![coinbase])),
+                system_tx(at(&blocks[2]), full_update(vec![coinbase])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_lines_listener() {
+        let path = std::env::temp_dir().join(format!("bsc-events-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let offender = address!("0x72b61c6014342d914470ec7ac2975be345796c2b");
+        let listener = JsonLinesListener::spawn(&path, 1).unwrap();
+        for number in [1, 2] {
+            let block = BlockNumHash::new(number, B256::with_last_byte(number as u8));
+            listener.on_slash(block, offender, Address::ZERO);
+        }
+        let dropped = listener.dropped();
+        drop(listener);
+
+        // the writer stops once the listener is dropped and the backlog is written
+        let mut lines = Vec::new();
+        for _ in 0..100 {
+            lines = std::fs::read_to_string(&path)
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .collect();
+            if lines.len() as u64 + dropped == 2 {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines.len() as u64 + dropped, 2);
+        assert_eq!(lines[0]["event"], "slash");
+        assert_eq!(lines[0]["block"], 1);
+        assert_eq!(lines[0]["hash"], B256::with_last_byte(1).to_string());
+        assert_eq!(lines[0]["offender"], offender.to_string().to_lowercase());
+    }
+
+    #[test]
+    fn test_slashing_listener() {
+        let offender = address!("0x72b61c6014342d914470ec7ac2975be345796c2b");
+        let beneficiary = address!("0xe2d3a739effcd3a99387d015e260eefac72ebea1");
+        let listener = SlashingListener::default();
+        // sending without subscribers is fine
+        listener.on_slash(BlockNumHash::new(1, B256::with_last_byte(1)), offender, beneficiary);
+
+        let mut events = listener.subscribe();
+        let hash = B256::with_last_byte(3);
+        listener.on_finality_reward(BlockNumHash::new(2, B256::with_last_byte(2)), &[]);
+        listener.on_slash(BlockNumHash::new(3, hash), offender, beneficiary);
+
+        assert_eq!(
+            events.try_recv(),
+            Ok(SlashEvent { block: 3, hash, validator: offender, beneficiary })
+        );
+        assert!(events.try_recv().is_err());
+    }
+}
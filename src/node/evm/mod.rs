@@ -8,11 +8,13 @@ use crate::{
 };
 use alloy_primitives::{Address, Bytes};
 use config::BscEvmConfig;
+use listener::{notify_canonical_blocks, BscExecutionListener, JsonLinesListener};
 use reth::{
     api::FullNodeTypes,
     builder::{components::ExecutorBuilder, BuilderContext},
 };
 use reth_evm::{precompiles::PrecompilesMap, Database, Evm, EvmEnv};
+use reth_provider::CanonStateSubscriptions;
 use revm::{
     context::{
         result::{EVMError, HaltReason, ResultAndState},
@@ -20,13 +22,21 @@ use revm::{
     },
     Context, ExecuteEvm, InspectEvm, Inspector, SystemCallEvm,
 };
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+#[cfg(debug_assertions)]
+use tracing::warn;
 
 mod assembler;
 pub mod config;
+pub mod error;
 mod executor;
 mod factory;
+pub mod listener;
 mod patch;
 
+pub use executor::{verify_no_duplicate_transactions, verify_system_txs_at_end};
+
 impl<DB, I> Evm for BscEvm<DB, I>
 where
     DB: Database,
@@ -52,6 +62,13 @@ where
         &mut self,
         tx: Self::Tx,
     ) -> Result<ResultAndState<Self::HaltReason>, Self::Error> {
+        if let Some(chain_id) = self.expected_chain_id {
+            debug_assert_eq!(
+                self.cfg.chain_id, chain_id,
+                "EVM environment built for another chain"
+            );
+        }
+
         if self.inspect {
             self.inspect_tx(tx)
         } else if tx.is_system_transaction {
@@ -113,10 +130,45 @@ where
     }
 }
 
+/// BSC specific execution arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Args)]
+pub struct BscEvmArgs {
+    /// Appends the slash, finality reward and validator set update transactions of the canonical
+    /// blocks as JSON lines to the given file.
+    #[arg(long = "bsc.events-file", value_name = "FILE")]
+    pub events_file: Option<PathBuf>,
+
+    /// Skips the system contract upgrades of hardfork blocks. The state root of an upgrade block
+    /// is expected to mismatch, comparing it against a regular execution tells whether a
+    /// mismatch stems from the upgraded bytecode. Debug builds only, never sync with it.
+    #[cfg(debug_assertions)]
+    #[arg(long = "bsc.debug.skip-system-contract-upgrades")]
+    pub skip_system_contract_upgrades: bool,
+}
+
 /// A regular bsc evm and executor builder.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct BscExecutorBuilder;
+pub struct BscExecutorBuilder {
+    /// Listeners notified about the system transactions of the canonical blocks.
+    listeners: Vec<Arc<dyn BscExecutionListener>>,
+    /// BSC specific execution arguments.
+    args: BscEvmArgs,
+}
+
+impl BscExecutorBuilder {
+    /// Sets the BSC specific execution arguments.
+    pub fn with_evm_args(mut self, args: BscEvmArgs) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Also notifies the given listener about the system transactions of the canonical blocks.
+    pub fn with_execution_listener(mut self, listener: Arc<dyn BscExecutionListener>) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+}
 
 impl<Node> ExecutorBuilder<Node> for BscExecutorBuilder
 where
@@ -125,7 +177,28 @@ where
     type EVM = BscEvmConfig;
 
     async fn build_evm(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::EVM> {
+        let mut listeners = self.listeners;
+        if let Some(path) = &self.args.events_file {
+            let listener = JsonLinesListener::spawn(path, JsonLinesListener::DEFAULT_CAPACITY)?;
+            info!(target: "reth::cli", path = %path.display(), "Writing execution events");
+            listeners.push(Arc::new(listener));
+        }
+        if !listeners.is_empty() {
+            let notifications = ctx.provider().canonical_state_stream();
+            ctx.task_executor().spawn(notify_canonical_blocks(listeners, notifications));
+        }
         let evm_config = BscEvmConfig::bsc(ctx.chain_spec());
+        #[cfg(debug_assertions)]
+        let evm_config = {
+            let skip = self.args.skip_system_contract_upgrades;
+            if skip {
+                warn!(
+                    target: "reth::cli",
+                    "Skipping system contract upgrades, state roots of upgrade blocks will mismatch"
+                );
+            }
+            evm_config.with_skip_system_contract_upgrades(skip)
+        };
         Ok(evm_config)
     }
 }
@@ -1,3 +1,4 @@
+use crate::hardforks::BscHardforks;
 use alloy_primitives::{address, b256, Address, B256, U256};
 use reth_evm::block::BlockExecutionError;
 use reth_primitives_traits::SignedTransaction;
@@ -674,6 +675,14 @@ static CHAPEL_PATCHES_AFTER_TX: LazyLock<HashMap<B256, StoragePatch>> = LazyLock
     ])
 });
 
+/// Returns whether the HertzFix storage patches apply at the given block.
+///
+/// The patches restore the storage of the transactions executed between Hertz and HertzFix,
+/// from HertzFix on the affected code path behaves correctly and nothing is patched.
+pub(crate) fn is_hertz_patch_block<Spec: BscHardforks>(spec: &Spec, block_number: u64) -> bool {
+    spec.is_hertz_active_at_block(block_number) && !spec.is_hertz_fix_active_at_block(block_number)
+}
+
 pub(crate) fn patch_mainnet_before_tx<DB, T>(
     transaction: &T,
     state: &mut State<DB>,
@@ -772,3 +781,65 @@ where
     state.apply_transition(vec![(address, account_change)]);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chainspec::{bsc::bsc_mainnet, bsc_chapel::bsc_testnet, BscChainSpec};
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::Signature;
+    use reth_primitives::TransactionSigned;
+    use revm::database::EmptyDB;
+
+    #[test]
+    fn test_hertz_patch_blocks() {
+        let mainnet = BscChainSpec::from(bsc_mainnet());
+        let chapel = BscChainSpec::from(bsc_testnet());
+
+        // every patched transaction lies between Hertz and HertzFix
+        assert!(is_hertz_patch_block(&mainnet, 33_851_236));
+        assert!(is_hertz_patch_block(&chapel, 35_547_779));
+        assert!(is_hertz_patch_block(&chapel, 35_548_081));
+
+        for (spec, hertz, hertz_fix) in
+            [(&mainnet, 31_302_048, 34_140_700), (&chapel, 31_103_030, 35_682_300)]
+        {
+            assert!(!is_hertz_patch_block(spec, hertz - 1));
+            assert!(is_hertz_patch_block(spec, hertz));
+            assert!(is_hertz_patch_block(spec, hertz_fix - 1));
+            assert!(!is_hertz_patch_block(spec, hertz_fix));
+        }
+    }
+
+    #[test]
+    fn test_patch_mainnet_tx() {
+        // patch 1 of block 33851236
+        let hash = b256!("7eba4edc7c1806d6ee1691d43513838931de5c94f9da56ec865721b402f775b0");
+        let tx = TransactionSigned::Legacy(Signed::new_unchecked(
+            TxLegacy::default(),
+            Signature::test_signature(),
+            hash,
+        ));
+        let contract = address!("00000000001f8b68515EfB546542397d3293CCfd");
+        let mut state = State::builder().with_database(EmptyDB::default()).build();
+
+        patch_mainnet_before_tx(&tx, &mut state).unwrap();
+        assert_eq!(
+            state.storage(contract, U256::from(1)).unwrap(),
+            U256::from_be_slice(&address!("52db206170b430da8223651d28830e56ba3cdc04")[..])
+        );
+
+        patch_mainnet_after_tx(&tx, &mut state).unwrap();
+        assert_eq!(state.storage(contract, U256::from(1)).unwrap(), U256::ZERO);
+
+        // other transactions are left alone
+        let other = TransactionSigned::Legacy(Signed::new_unchecked(
+            TxLegacy::default(),
+            Signature::test_signature(),
+            B256::repeat_byte(0x11),
+        ));
+        patch_chapel_before_tx(&other, &mut state).unwrap();
+        patch_mainnet_before_tx(&other, &mut state).unwrap();
+        assert_eq!(state.storage(contract, U256::from(1)).unwrap(), U256::ZERO);
+    }
+}
@@ -0,0 +1,277 @@
+//! Health report summarizing the BSC specific sync invariants of the node.
+//!
+//! The report is evaluated from [`HealthInputs`], values the node already keeps: the canonical
+//! headers, the finalized block and the peers tracked by the block import. Collecting them reads
+//! a bounded number of headers and never triggers any rebuild, so the report is cheap enough to
+//! be polled by a load balancer.
+
+use crate::{
+    chainspec::BscChainSpec,
+    consensus::{block_interval_ms, rotation_at},
+    node::{consensus::calculate_millisecond_timestamp, network::peer_heads::PeerHeadTracker},
+};
+use alloy_consensus::Header;
+use alloy_primitives::BlockNumber;
+use reth_chainspec::ChainSpecProvider;
+use reth_provider::{BlockReaderIdExt, ProviderError};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a health check, ordered by severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The invariant holds.
+    Pass,
+    /// The invariant holds only loosely, the node keeps serving.
+    Warn,
+    /// The invariant is violated, the node shouldn't serve.
+    Fail,
+}
+
+impl HealthStatus {
+    /// Returns the HTTP status code a load balancer expects for this status: `200` unless the
+    /// node failed a check, `503` otherwise.
+    pub const fn http_code(self) -> u16 {
+        match self {
+            Self::Pass | Self::Warn => 200,
+            Self::Fail => 503,
+        }
+    }
+}
+
+/// Outcome of a single health check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    /// Name of the check.
+    pub name: String,
+    /// Outcome of the check.
+    pub status: HealthStatus,
+    /// Details of the outcome.
+    pub message: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, status: HealthStatus, message: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status, message: message.into() }
+    }
+}
+
+/// Outcome of all health checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Most severe outcome of the checks.
+    pub status: HealthStatus,
+    /// HTTP status code of the overall outcome.
+    pub http_code: u16,
+    /// Outcome of each check.
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Limits the health checks are evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+    /// Number of most recent canonical headers that have to be available.
+    pub header_window: u64,
+    /// Blocks the finalized block may lag behind the head before the check warns.
+    pub finality_lag_warn: u64,
+    /// Blocks the finalized block may lag behind the head before the check fails.
+    pub finality_lag_fail: u64,
+    /// Delay past the block interval after which a missing block is reported as a warning.
+    pub import_grace_ms: u64,
+    /// Delay past the block interval after which a missing block fails the check.
+    pub import_stall_ms: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            header_window: 64,
+            finality_lag_warn: 32,
+            finality_lag_fail: 1_024,
+            import_grace_ms: 3_000,
+            import_stall_ms: 60_000,
+        }
+    }
+}
+
+/// Values the health checks are evaluated on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthInputs {
+    /// Number of the canonical head.
+    pub head: BlockNumber,
+    /// Millisecond timestamp of the canonical head.
+    pub head_timestamp_ms: u64,
+    /// Interval between blocks of the hardforks active at the head.
+    pub block_interval_ms: u64,
+    /// Number of the most recent canonical headers available, up to the header window.
+    pub headers: u64,
+    /// Size of the validator rotation in effect at the head, or why it can't be read.
+    pub rotation: Result<usize, String>,
+    /// Number of the finalized block, if any.
+    pub finalized: Option<BlockNumber>,
+    /// Number of connected peers that announced a valid block.
+    pub peers: usize,
+}
+
+impl HealthInputs {
+    /// Reads the inputs of the checks from the node.
+    pub fn collect<Provider>(
+        provider: &Provider,
+        peer_heads: &PeerHeadTracker,
+        thresholds: &HealthThresholds,
+    ) -> Result<Self, ProviderError>
+    where
+        Provider: BlockReaderIdExt<Header = Header> + ChainSpecProvider<ChainSpec = BscChainSpec>,
+    {
+        let chain_spec = provider.chain_spec();
+        let head = provider.latest_header()?.ok_or(ProviderError::BestBlockNotFound)?;
+        let window = head.number.saturating_sub(thresholds.header_window.saturating_sub(1));
+        let headers = provider.headers_range(window..=head.number)?.len() as u64;
+        let rotation = rotation_at(&*chain_spec, provider, head.number)
+            .map(|rotation| rotation.validators().len())
+            .map_err(|err| err.to_string());
+
+        Ok(Self {
+            head: head.number,
+            head_timestamp_ms: calculate_millisecond_timestamp(head.header()),
+            block_interval_ms: block_interval_ms(&*chain_spec, head.timestamp),
+            headers,
+            rotation,
+            finalized: provider.finalized_block_number()?,
+            peers: peer_heads.len(),
+        })
+    }
+
+    /// Evaluates the checks at the given time, in milliseconds since the Unix epoch.
+    pub fn evaluate(&self, thresholds: &HealthThresholds, now_ms: u64) -> HealthReport {
+        use HealthStatus::*;
+
+        let expected = thresholds.header_window.min(self.head + 1);
+        let headers = if self.headers < expected {
+            HealthCheck::new(
+                "headers",
+                Fail,
+                format!("{} of the last {expected} headers missing", expected - self.headers),
+            )
+        } else {
+            HealthCheck::new("headers", Pass, format!("last {expected} headers available"))
+        };
+
+        let rotation = match &self.rotation {
+            Ok(validators) => HealthCheck::new(
+                "rotation",
+                Pass,
+                format!("{validators} validators in turn at block {}", self.head),
+            ),
+            Err(err) => HealthCheck::new("rotation", Fail, err.clone()),
+        };
+
+        let finality = match self.finalized {
+            Some(finalized) => {
+                let lag = self.head.saturating_sub(finalized);
+                let status = if lag > thresholds.finality_lag_fail {
+                    Fail
+                } else if lag > thresholds.finality_lag_warn {
+                    Warn
+                } else {
+                    Pass
+                };
+                HealthCheck::new("finality", status, format!("finalized block {lag} behind head"))
+            }
+            None => HealthCheck::new("finality", Warn, "no finalized block"),
+        };
+
+        let peers = if self.peers == 0 {
+            HealthCheck::new("peers", Fail, "no peer announced a valid block")
+        } else {
+            HealthCheck::new("peers", Pass, format!("{} peers announced valid blocks", self.peers))
+        };
+
+        let age = now_ms.saturating_sub(self.head_timestamp_ms);
+        let overdue = age.saturating_sub(self.block_interval_ms);
+        let status = if overdue > thresholds.import_stall_ms {
+            Fail
+        } else if overdue > thresholds.import_grace_ms {
+            Warn
+        } else {
+            Pass
+        };
+        let import = HealthCheck::new(
+            "import",
+            status,
+            format!("head sealed {age}ms ago, blocks are due every {}ms", self.block_interval_ms),
+        );
+
+        let checks = vec![headers, rotation, finality, peers, import];
+        let status = checks.iter().map(|check| check.status).max().unwrap_or(Pass);
+        HealthReport { status, http_code: status.http_code(), checks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> HealthInputs {
+        HealthInputs {
+            head: 52_000_000,
+            head_timestamp_ms: 1_751_250_600_000,
+            block_interval_ms: 750,
+            headers: 64,
+            rotation: Ok(21),
+            finalized: Some(51_999_998),
+            peers: 8,
+        }
+    }
+
+    fn status(report: &HealthReport, name: &str) -> HealthStatus {
+        report.checks.iter().find(|check| check.name == name).unwrap().status
+    }
+
+    #[test]
+    fn test_health_report() {
+        let thresholds = HealthThresholds::default();
+        let now = 1_751_250_600_750;
+
+        let report = healthy().evaluate(&thresholds, now);
+        assert!(report.checks.iter().all(|check| check.status == HealthStatus::Pass));
+        assert_eq!((report.status, report.http_code), (HealthStatus::Pass, 200));
+
+        // a lagging finalized block only warns
+        let inputs = HealthInputs { finalized: Some(51_999_900), ..healthy() };
+        let report = inputs.evaluate(&thresholds, now);
+        assert_eq!(status(&report, "finality"), HealthStatus::Warn);
+        assert_eq!((report.status, report.http_code), (HealthStatus::Warn, 200));
+
+        // a block overdue by more than the grace period warns, a stalled import fails
+        let report = healthy().evaluate(&thresholds, now + 5_000);
+        assert_eq!(
+            (status(&report, "import"), report.status),
+            (HealthStatus::Warn, HealthStatus::Warn)
+        );
+        let report = healthy().evaluate(&thresholds, now + 120_000);
+        assert_eq!(
+            (status(&report, "import"), report.status),
+            (HealthStatus::Fail, HealthStatus::Fail)
+        );
+
+        // a failing check outweighs any warning
+        let inputs = HealthInputs { peers: 0, finalized: None, ..healthy() };
+        let report = inputs.evaluate(&thresholds, now);
+        assert_eq!(status(&report, "finality"), HealthStatus::Warn);
+        assert_eq!(status(&report, "peers"), HealthStatus::Fail);
+        assert_eq!((report.status, report.http_code), (HealthStatus::Fail, 503));
+
+        let inputs = HealthInputs { headers: 60, ..healthy() };
+        assert_eq!(inputs.evaluate(&thresholds, now).status, HealthStatus::Fail);
+        let inputs =
+            HealthInputs { rotation: Err("Header 51999999 not found".into()), ..healthy() };
+        assert_eq!(inputs.evaluate(&thresholds, now).status, HealthStatus::Fail);
+
+        // a chain shorter than the window has all of its headers
+        let inputs = HealthInputs { head: 9, headers: 10, finalized: Some(9), ..healthy() };
+        assert_eq!(status(&inputs.evaluate(&thresholds, now), "headers"), HealthStatus::Pass);
+    }
+}
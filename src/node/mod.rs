@@ -1,5 +1,6 @@
 use crate::{
     chainspec::BscChainSpec,
+    consensus::{ProposerArchive, ValidatorPerfTracker},
     node::{
         engine_api::{
             builder::BscEngineApiBuilder, payload::BscPayloadTypes,
@@ -12,44 +13,131 @@ use crate::{
 };
 use consensus::BscConsensusBuilder;
 use engine::BscPayloadServiceBuilder;
-use evm::BscExecutorBuilder;
-use network::BscNetworkBuilder;
+use evm::{listener::BscExecutionListener, BscEvmArgs, BscExecutorBuilder};
+use network::{
+    block_import::service::{HeadOverride, RpcBlockImport},
+    engine_handle::{self, EngineHandleReceiver, EngineHandleSender},
+    peer_heads::PeerHeadTracker,
+    BscNetworkArgs, BscNetworkBuilder,
+};
+use pool::BscPoolBuilder;
 use reth::{
     api::{FullNodeComponents, FullNodeTypes, NodeTypes},
     builder::{components::ComponentsBuilder, rpc::RpcAddOns, DebugNode, Node, NodeAdapter},
 };
 use reth_engine_local::LocalPayloadAttributesBuilder;
-use reth_engine_primitives::BeaconConsensusEngineHandle;
-use reth_node_ethereum::{node::EthereumPoolBuilder, EthereumEthApiBuilder};
+use reth_node_ethereum::EthereumEthApiBuilder;
 use reth_payload_primitives::{PayloadAttributesBuilder, PayloadTypes};
 use reth_primitives::BlockBody;
 use reth_trie_db::MerklePatriciaTrie;
 use std::sync::Arc;
-use tokio::sync::{oneshot, Mutex};
 
 pub mod consensus;
+pub mod datadir;
 pub mod engine;
 pub mod engine_api;
 pub mod evm;
+pub mod health;
 pub mod network;
+pub mod pool;
 pub mod primitives;
+pub mod rpc;
 pub mod storage;
 
 /// Bsc addons configuring RPC types
 pub type BscNodeAddOns<N> =
     RpcAddOns<N, EthereumEthApiBuilder, BscEngineValidatorBuilder, BscEngineApiBuilder>;
 
+/// BSC specific command line arguments of the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Args)]
+pub struct BscArgs {
+    /// Networking arguments.
+    #[command(flatten)]
+    pub network: BscNetworkArgs,
+    /// Execution arguments.
+    #[command(flatten)]
+    pub evm: BscEvmArgs,
+}
+
 /// Type configuration for a regular BSC node.
 #[derive(Debug, Clone)]
 pub struct BscNode {
-    engine_handle_rx:
-        Arc<Mutex<Option<oneshot::Receiver<BeaconConsensusEngineHandle<BscPayloadTypes>>>>>,
+    engine_handle_rx: EngineHandleReceiver,
+    network_args: BscNetworkArgs,
+    evm_args: BscEvmArgs,
+    peer_heads: PeerHeadTracker,
+    head_override: HeadOverride,
+    rpc_block_import: RpcBlockImport,
+    execution_listeners: Vec<Arc<dyn BscExecutionListener>>,
+    proposer_archive: Option<ProposerArchive>,
+    performance_tracker: Option<ValidatorPerfTracker>,
 }
 
 impl BscNode {
-    pub fn new() -> (Self, oneshot::Sender<BeaconConsensusEngineHandle<BscPayloadTypes>>) {
-        let (tx, rx) = oneshot::channel();
-        (Self { engine_handle_rx: Arc::new(Mutex::new(Some(rx))) }, tx)
+    /// Creates a new node together with the sender used to publish the engine handle after launch.
+    pub fn new() -> (Self, EngineHandleSender) {
+        let (tx, rx) = engine_handle::channel();
+        let node = Self {
+            engine_handle_rx: rx,
+            network_args: BscNetworkArgs::default(),
+            evm_args: BscEvmArgs::default(),
+            peer_heads: PeerHeadTracker::default(),
+            head_override: HeadOverride::default(),
+            rpc_block_import: RpcBlockImport::default(),
+            execution_listeners: Vec::new(),
+            proposer_archive: None,
+            performance_tracker: None,
+        };
+        (node, tx)
+    }
+
+    /// Returns the tracker of the heads announced by peers, which hints at the sync target.
+    pub const fn peer_heads(&self) -> &PeerHeadTracker {
+        &self.peer_heads
+    }
+
+    /// Returns the override pinning the fork choice, which allows moving or releasing the pin
+    /// set by `--debug.tip` at runtime.
+    pub const fn head_override(&self) -> &HeadOverride {
+        &self.head_override
+    }
+
+    /// Returns the handle feeding blocks received via RPC into the block import.
+    pub const fn rpc_block_import(&self) -> &RpcBlockImport {
+        &self.rpc_block_import
+    }
+
+    /// Sets the BSC specific networking arguments.
+    pub const fn with_network_args(mut self, network_args: BscNetworkArgs) -> Self {
+        self.network_args = network_args;
+        self
+    }
+
+    /// Sets the BSC specific execution arguments.
+    pub fn with_evm_args(mut self, evm_args: BscEvmArgs) -> Self {
+        self.evm_args = evm_args;
+        self
+    }
+
+    /// Also notifies the given listener about the slash, finality reward and validator set
+    /// update transactions of the canonical blocks.
+    pub fn with_execution_listener(mut self, listener: Arc<dyn BscExecutionListener>) -> Self {
+        self.execution_listeners.push(listener);
+        self
+    }
+
+    /// Records the proposer of every canonical block, and whether it was in turn, in the given
+    /// archive.
+    pub fn with_proposer_archive(mut self, archive: ProposerArchive) -> Self {
+        self.proposer_archive = Some(archive);
+        self
+    }
+
+    /// Counts the in-turn, out-of-turn and missed slots of every validator over the canonical
+    /// blocks in the given tracker.
+    pub fn with_performance_tracker(mut self, tracker: ValidatorPerfTracker) -> Self {
+        self.performance_tracker = Some(tracker);
+        self
     }
 }
 
@@ -58,7 +146,7 @@ impl BscNode {
         &self,
     ) -> ComponentsBuilder<
         Node,
-        EthereumPoolBuilder,
+        BscPoolBuilder,
         BscPayloadServiceBuilder,
         BscNetworkBuilder,
         BscExecutorBuilder,
@@ -67,13 +155,31 @@ impl BscNode {
     where
         Node: FullNodeTypes<Types = Self>,
     {
+        let mut executor = BscExecutorBuilder::default().with_evm_args(self.evm_args.clone());
+        for listener in &self.execution_listeners {
+            executor = executor.with_execution_listener(listener.clone());
+        }
+        let mut consensus = BscConsensusBuilder::default();
+        if let Some(archive) = &self.proposer_archive {
+            consensus = consensus.with_proposer_archive(archive.clone());
+        }
+        if let Some(tracker) = &self.performance_tracker {
+            consensus = consensus.with_performance_tracker(tracker.clone());
+        }
+
         ComponentsBuilder::default()
             .node_types::<Node>()
-            .pool(EthereumPoolBuilder::default())
-            .executor(BscExecutorBuilder::default())
+            .pool(BscPoolBuilder::default())
+            .executor(executor)
             .payload(BscPayloadServiceBuilder::default())
-            .network(BscNetworkBuilder { engine_handle_rx: self.engine_handle_rx.clone() })
-            .consensus(BscConsensusBuilder::default())
+            .network(
+                BscNetworkBuilder::new(self.engine_handle_rx.clone())
+                    .with_min_peers(self.network_args.min_validator_peers)
+                    .with_peer_heads(self.peer_heads.clone())
+                    .with_head_override(self.head_override.clone())
+                    .with_rpc_block_import(self.rpc_block_import.clone()),
+            )
+            .consensus(consensus)
     }
 }
 
@@ -91,7 +197,7 @@ where
 {
     type ComponentsBuilder = ComponentsBuilder<
         N,
-        EthereumPoolBuilder,
+        BscPoolBuilder,
         BscPayloadServiceBuilder,
         BscNetworkBuilder,
         BscExecutorBuilder,
@@ -0,0 +1,245 @@
+//! Fetching of blocks announced by hash.
+//!
+//! BSC peers send the full `NewBlock` to a few peers only and announce the block to the others
+//! via `NewBlockHashes`. Announced blocks that didn't arrive in full are downloaded from the
+//! network and handed to the block import like a `NewBlock` message.
+
+use super::service::IncomingBlock;
+use crate::{node::network::BscNewBlock, BscBlock};
+use alloy_primitives::{B256, U128};
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use reth::network::cache::LruCache;
+use reth_eth_wire::{NewBlock, NewBlockHashes};
+use reth_network::message::NewBlockMessage;
+use reth_network_api::PeerId;
+use reth_network_p2p::{full_block::FullBlockClient, BlockClient};
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::debug;
+
+/// Default time to wait for a single download of an announced block.
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Number of downloads of an announced block before it is given up.
+pub const MAX_FETCH_ATTEMPTS: usize = 3;
+
+/// Size of the LRU cache of blocks that were received or fetched already.
+const LRU_KNOWN_BLOCKS_SIZE: u32 = 100;
+
+/// Future downloading an announced block, resolves to the hash, the announcing peer and the
+/// block if it could be downloaded.
+type DownloadFut = Pin<Box<dyn Future<Output = (B256, PeerId, Option<BscBlock>)> + Send>>;
+
+/// Block announcement forwarded by the network.
+#[derive(Debug)]
+pub enum Announcement {
+    /// Blocks announced by hash by the given peer.
+    Hashes(NewBlockHashes, PeerId),
+    /// A block received in full, which doesn't need to be fetched.
+    Block(B256),
+}
+
+/// Downloads blocks by hash.
+pub trait BlockDownloader: Send + Sync + 'static {
+    /// Downloads the block with the given hash.
+    ///
+    /// The future only resolves once the block was downloaded, it is up to the caller to give up.
+    fn download(&self, hash: B256) -> BoxFuture<'static, BscBlock>;
+}
+
+impl<Client> BlockDownloader for FullBlockClient<Client>
+where
+    Client: BlockClient<Block = BscBlock> + 'static,
+{
+    fn download(&self, hash: B256) -> BoxFuture<'static, BscBlock> {
+        // the client asks the best peer and moves on to the next one on a bad or missing response
+        let block = self.get_full_block(hash);
+        Box::pin(async move { block.await.into_block() })
+    }
+}
+
+/// A service fetching the blocks announced by hash and sending them to the block import.
+pub struct BlockFetcher<D> {
+    /// Downloads the announced blocks.
+    downloader: Arc<D>,
+    /// Receive the announcements from the network
+    announcements: UnboundedReceiver<Announcement>,
+    /// Send the fetched blocks to the block import
+    to_import: UnboundedSender<IncomingBlock>,
+    /// Blocks being downloaded.
+    in_flight: HashSet<B256>,
+    /// Blocks received in full, fetched or given up.
+    known_blocks: LruCache<B256>,
+    /// Pending downloads.
+    downloads: FuturesUnordered<DownloadFut>,
+    /// Maximum time to wait for a single download.
+    timeout: Duration,
+}
+
+impl<D: BlockDownloader> BlockFetcher<D> {
+    /// Creates a new block fetcher.
+    pub fn new(
+        downloader: D,
+        announcements: UnboundedReceiver<Announcement>,
+        to_import: UnboundedSender<IncomingBlock>,
+    ) -> Self {
+        Self {
+            downloader: Arc::new(downloader),
+            announcements,
+            to_import,
+            in_flight: HashSet::new(),
+            known_blocks: LruCache::new(LRU_KNOWN_BLOCKS_SIZE),
+            downloads: FuturesUnordered::new(),
+            timeout: DEFAULT_FETCH_TIMEOUT,
+        }
+    }
+
+    /// Sets the maximum time to wait for a single download.
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn on_announcement(&mut self, announcement: Announcement) {
+        let (hashes, peer_id) = match announcement {
+            Announcement::Hashes(hashes, peer_id) => (hashes, peer_id),
+            Announcement::Block(hash) => {
+                self.known_blocks.insert(hash);
+                return;
+            }
+        };
+
+        for announced in hashes.0 {
+            let hash = announced.hash;
+            if self.known_blocks.contains(&hash) || !self.in_flight.insert(hash) {
+                continue;
+            }
+
+            let downloader = self.downloader.clone();
+            let timeout = self.timeout;
+            self.downloads.push(Box::pin(async move {
+                for attempt in 1..=MAX_FETCH_ATTEMPTS {
+                    let download = tokio::time::timeout(timeout, downloader.download(hash));
+                    if let Ok(block) = download.await {
+                        return (hash, peer_id, Some(block));
+                    }
+                    debug!(target: "net::block_import", %hash, attempt, "Block fetch timed out");
+                }
+                (hash, peer_id, None)
+            }));
+        }
+    }
+}
+
+impl<D: BlockDownloader> Future for BlockFetcher<D> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // Receive announcements from the network, which is shutting down once the channel closes
+        loop {
+            match this.announcements.poll_recv(cx) {
+                Poll::Ready(Some(announcement)) => this.on_announcement(announcement),
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => break,
+            }
+        }
+
+        // Hand the fetched blocks to the block import
+        while let Poll::Ready(Some((hash, peer_id, block))) = this.downloads.poll_next_unpin(cx) {
+            this.in_flight.remove(&hash);
+            this.known_blocks.insert(hash);
+            let Some(block) = block else {
+                debug!(target: "net::block_import", %hash, "Giving up fetching announced block");
+                continue;
+            };
+
+            // the total difficulty isn't part of a hash announcement, the import doesn't rely on
+            // it
+            let block = NewBlockMessage {
+                hash,
+                block: Arc::new(BscNewBlock(NewBlock { block, td: U128::ZERO })),
+            };
+            if this.to_import.send((block, peer_id)).is_err() {
+                return Poll::Ready(());
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_eth_wire::BlockHashNumber;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::mpsc;
+
+    /// Serves the block on every download but the first, which never completes, as if the first
+    /// peer asked timed out and the next one served the block.
+    struct FallbackDownloader {
+        block: BscBlock,
+        downloads: Arc<AtomicUsize>,
+    }
+
+    impl BlockDownloader for FallbackDownloader {
+        fn download(&self, _hash: B256) -> BoxFuture<'static, BscBlock> {
+            if self.downloads.fetch_add(1, Ordering::SeqCst) == 0 {
+                Box::pin(futures::future::pending())
+            } else {
+                Box::pin(futures::future::ready(self.block.clone()))
+            }
+        }
+    }
+
+    fn announce(hash: B256, number: u64, peer_id: PeerId) -> Announcement {
+        Announcement::Hashes(NewBlockHashes(vec![BlockHashNumber { hash, number }]), peer_id)
+    }
+
+    #[tokio::test]
+    async fn fetches_announced_block_after_timeout() {
+        let mut block = BscBlock::default();
+        block.header.number = 7;
+        let hash = block.header.hash_slow();
+        let downloads = Arc::new(AtomicUsize::new(0));
+        let downloader = FallbackDownloader { block: block.clone(), downloads: downloads.clone() };
+
+        let (to_fetcher, announcements) = mpsc::unbounded_channel();
+        let (to_import, mut from_fetcher) = mpsc::unbounded_channel();
+        let fetcher = BlockFetcher::new(downloader, announcements, to_import)
+            .with_timeout(Duration::from_millis(50));
+        tokio::spawn(fetcher);
+
+        // the same block announced by two peers is downloaded once
+        let (first, second) = (PeerId::random(), PeerId::random());
+        to_fetcher.send(announce(hash, 7, first)).unwrap();
+        to_fetcher.send(announce(hash, 7, second)).unwrap();
+
+        let (fetched, peer_id) = tokio::time::timeout(Duration::from_secs(5), from_fetcher.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.hash, hash);
+        assert_eq!(fetched.block.0.block, block);
+        assert_eq!(peer_id, first);
+        assert_eq!(downloads.load(Ordering::SeqCst), 2);
+
+        // fetched blocks and blocks received in full aren't downloaded again
+        let other = B256::repeat_byte(0x11);
+        to_fetcher.send(Announcement::Block(other)).unwrap();
+        to_fetcher.send(announce(hash, 7, second)).unwrap();
+        to_fetcher.send(announce(other, 8, second)).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(from_fetcher.try_recv().is_err());
+        assert_eq!(downloads.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,7 +1,10 @@
 #![allow(unused)]
+use fetcher::Announcement;
 use handle::ImportHandle;
 use reth_engine_primitives::EngineTypes;
-use reth_network::import::{BlockImport, BlockImportOutcome, NewBlockEvent};
+use reth_network::import::{
+    BlockImport, BlockImportEvent, BlockImportOutcome, BlockValidation, NewBlockEvent,
+};
 use reth_network_peers::PeerId;
 use reth_payload_primitives::{BuiltPayload, PayloadTypes};
 use reth_primitives::NodePrimitives;
@@ -10,34 +13,126 @@ use std::{
     fmt,
     task::{ready, Context, Poll},
 };
+use tokio::sync::mpsc::UnboundedSender;
 
-use crate::node::network::BscNewBlock;
+use crate::node::network::{
+    peer_heads::{PeerHead, PeerHeadTracker},
+    BscNewBlock,
+};
 
+pub mod fetcher;
 pub mod handle;
 pub mod service;
 
 #[derive(Debug)]
 pub struct BscBlockImport {
     handle: ImportHandle,
+    /// Records the heads announced by peers, once the engine validated them.
+    peer_heads: PeerHeadTracker,
+    /// Forwards the announcements to the fetcher of the blocks announced by hash.
+    to_fetcher: Option<UnboundedSender<Announcement>>,
 }
 
 impl BscBlockImport {
-    pub fn new(handle: ImportHandle) -> Self {
-        Self { handle }
+    pub fn new(handle: ImportHandle, peer_heads: PeerHeadTracker) -> Self {
+        Self { handle, peer_heads, to_fetcher: None }
+    }
+
+    /// Forwards the announcements to the given block fetcher, without one blocks announced by
+    /// hash are ignored.
+    pub fn with_block_fetcher(mut self, to_fetcher: UnboundedSender<Announcement>) -> Self {
+        self.to_fetcher = Some(to_fetcher);
+        self
+    }
+
+    fn notify_fetcher(&self, announcement: Announcement) {
+        if let Some(to_fetcher) = &self.to_fetcher {
+            let _ = to_fetcher.send(announcement);
+        }
     }
 }
 
 impl BlockImport<BscNewBlock> for BscBlockImport {
     fn on_new_block(&mut self, peer_id: PeerId, incoming_block: NewBlockEvent<BscNewBlock>) {
-        if let NewBlockEvent::Block(block) = incoming_block {
-            let _ = self.handle.send_block(block, peer_id);
+        match incoming_block {
+            NewBlockEvent::Block(block) => {
+                self.notify_fetcher(Announcement::Block(block.hash));
+                let _ = self.handle.send_block(block, peer_id);
+            }
+            NewBlockEvent::Hashes(hashes) => {
+                self.notify_fetcher(Announcement::Hashes(hashes, peer_id));
+            }
         }
     }
 
     fn poll(&mut self, cx: &mut Context<'_>) -> Poll<ImportEvent> {
         match ready!(self.handle.poll_outcome(cx)) {
-            Some(outcome) => Poll::Ready(outcome),
+            Some(outcome) => {
+                // a peer's head only counts once the block turned out to be valid
+                if let BlockImportEvent::Outcome(BlockImportOutcome {
+                    peer,
+                    result: Ok(BlockValidation::ValidBlock { block }),
+                }) = &outcome
+                {
+                    let header = &block.block.0.block.header;
+                    let head = PeerHead {
+                        number: header.number,
+                        hash: block.hash,
+                        difficulty: header.difficulty,
+                    };
+                    self.peer_heads.on_new_block(*peer, head);
+                }
+                Poll::Ready(outcome)
+            }
             None => Poll::Pending,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BscBlock;
+    use alloy_primitives::{U128, U256};
+    use reth_eth_wire::NewBlock;
+    use reth_network::{import::BlockImportError, message::NewBlockMessage};
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn records_peer_head_after_valid_outcome() {
+        let (to_import, _from_network) = mpsc::unbounded_channel();
+        let (to_network, import_outcome) = mpsc::unbounded_channel();
+        let peer_heads = PeerHeadTracker::default();
+        let mut import =
+            BscBlockImport::new(ImportHandle::new(to_import, import_outcome), peer_heads.clone());
+
+        let mut block = BscBlock::default();
+        block.header.number = 7;
+        block.header.difficulty = U256::from(2);
+        let hash = block.header.hash_slow();
+        let block = NewBlockMessage {
+            hash,
+            block: Arc::new(BscNewBlock(NewBlock { block, td: U128::from(14) })),
+        };
+        let (honest, liar) = (PeerId::random(), PeerId::random());
+
+        // the announcement alone doesn't move the peer's head, neither does an invalid block
+        import.on_new_block(liar, NewBlockEvent::Block(block.clone()));
+        let outcome = |peer, result| BlockImportEvent::Outcome(Outcome { peer, result });
+        let invalid = Err(BlockImportError::Other("invalid".into()));
+        to_network.send(outcome(liar, invalid)).unwrap();
+        to_network.send(outcome(honest, Ok(BlockValidation::ValidBlock { block }))).unwrap();
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(import.poll(&mut cx).is_ready());
+        assert!(peer_heads.is_empty());
+        assert!(import.poll(&mut cx).is_ready());
+        assert_eq!(
+            peer_heads.best_target(),
+            Some(PeerHead { number: 7, hash, difficulty: U256::from(2) })
+        );
+        assert_eq!(peer_heads.len(), 1);
+    }
+}
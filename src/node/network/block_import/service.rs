@@ -1,13 +1,18 @@
 use super::handle::ImportHandle;
 use crate::{
     consensus::{ParliaConsensus, ParliaConsensusErr},
-    node::{engine_api::payload::BscPayloadTypes, network::BscNewBlock},
+    node::{
+        consensus::validate_no_ommers,
+        engine_api::payload::BscPayloadTypes,
+        network::{peer_heads::PeerHeadTracker, BscNewBlock},
+    },
     BscBlock, BscBlockBody,
 };
 use alloy_consensus::{BlockBody, Header};
-use alloy_primitives::{B256, U128};
+use alloy_primitives::B256;
 use alloy_rpc_types::engine::{ForkchoiceState, PayloadStatusEnum};
 use futures::{future::Either, stream::FuturesUnordered, StreamExt};
+use parking_lot::Mutex;
 use reth::network::cache::LruCache;
 use reth_engine_primitives::{BeaconConsensusEngineHandle, EngineTypes};
 use reth_network::{
@@ -17,16 +22,25 @@ use reth_network::{
 use reth_network_api::PeerId;
 use reth_node_ethereum::EthEngineTypes;
 use reth_payload_primitives::{BuiltPayload, EngineApiMessageVersion, PayloadTypes};
-use reth_primitives::NodePrimitives;
-use reth_primitives_traits::{AlloyBlockHeader, Block};
-use reth_provider::{BlockHashReader, BlockNumReader};
+use reth_primitives::{NodePrimitives, SealedBlock};
+use reth_primitives_traits::Block;
+use reth_provider::{BlockNumReader, HeaderProvider};
+use serde::{Deserialize, Serialize};
 use std::{
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
-use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    time::Interval,
+};
+use tracing::{debug, warn};
 
 /// Network message containing a new block
 pub(crate) type BlockMsg = NewBlockMessage<BscNewBlock>;
@@ -43,9 +57,100 @@ type ImportFut = Pin<Box<dyn Future<Output = Option<Outcome>> + Send + Sync>>;
 /// Channel message type for incoming blocks
 pub(crate) type IncomingBlock = (BlockMsg, PeerId);
 
+/// Block received via RPC, together with the sender of its import status.
+pub(crate) type RpcImport = (BlockMsg, oneshot::Sender<ImportStatus>);
+
 /// Size of the LRU cache for processed blocks.
 const LRU_PROCESSED_BLOCKS_SIZE: u32 = 100;
 
+/// Default timeout for a single `newPayload` or `forkchoiceUpdated` call to the engine.
+pub const DEFAULT_ENGINE_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the local head is compared with the best head of the peers.
+pub const PEER_HEAD_SYNC_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Pins the fork choice to a given block, see `--debug.tip`.
+///
+/// While a block is pinned, blocks arriving from the network are still sent to the engine via
+/// `newPayload`, but the only fork choice update sent is the one for the pinned block itself.
+///
+/// The override is cheap to clone, all clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct HeadOverride {
+    inner: Arc<Mutex<Option<B256>>>,
+}
+
+impl HeadOverride {
+    /// Pins the fork choice to the given block, or releases the pin if `None`.
+    pub fn set(&self, hash: Option<B256>) {
+        *self.inner.lock() = hash;
+    }
+
+    /// Returns the pinned block, if any.
+    pub fn get(&self) -> Option<B256> {
+        *self.inner.lock()
+    }
+}
+
+/// Status of a block imported via RPC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ImportStatus {
+    /// The engine accepted the block and its fork choice update.
+    Valid,
+    /// The block or its fork choice update was rejected.
+    Invalid {
+        /// Reason of the rejection.
+        error: String,
+    },
+    /// The engine didn't decide on the block, e.g. because it is syncing or didn't answer in time.
+    Unknown,
+}
+
+impl ImportStatus {
+    fn from_outcome(outcome: Option<Outcome>) -> Self {
+        match outcome.map(|outcome| outcome.result) {
+            Some(Ok(_)) => Self::Valid,
+            Some(Err(err)) => Self::Invalid { error: err.to_string() },
+            None => Self::Unknown,
+        }
+    }
+}
+
+/// Feeds blocks received via RPC into the block import service.
+///
+/// The handle is connected when the block import service is built, until then imports fail.
+/// It is cheap to clone, all clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct RpcBlockImport {
+    to_import: Arc<Mutex<Option<UnboundedSender<RpcImport>>>>,
+}
+
+impl RpcBlockImport {
+    /// Connects the handle to a new channel and returns its receiving end.
+    fn connect(&self) -> UnboundedReceiver<RpcImport> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *self.to_import.lock() = Some(tx);
+        rx
+    }
+
+    /// Imports the block the same way as a block announced by a peer and returns its status.
+    pub async fn import_block(&self, block: BscNewBlock) -> Result<ImportStatus, BlockImportError> {
+        let hash = block.0.block.header.hash_slow();
+        let block = NewBlockMessage { hash, block: Arc::new(block) };
+        let (tx, rx) = oneshot::channel();
+        let sent = self
+            .to_import
+            .lock()
+            .as_ref()
+            .is_some_and(|to_import| to_import.send((block, tx)).is_ok());
+        if !sent {
+            return Err(BlockImportError::Other("block import service not running".into()))
+        }
+        rx.await.map_err(|_| BlockImportError::Other("block import service channel closed".into()))
+    }
+}
+
 /// A service that handles bidirectional block import communication with the network.
 /// It receives new blocks from the network via `from_network` channel and sends back
 /// import outcomes via `to_network` channel.
@@ -59,19 +164,31 @@ where
     consensus: Arc<ParliaConsensus<Provider>>,
     /// Receive the new block from the network
     from_network: UnboundedReceiver<IncomingBlock>,
+    /// Receive the blocks imported via RPC
+    from_rpc: Option<UnboundedReceiver<RpcImport>>,
     /// Send the event of the import to the network
     to_network: UnboundedSender<ImportEvent>,
     /// Pending block imports.
     pending_imports: FuturesUnordered<ImportFut>,
     /// Cache of processed block hashes to avoid reprocessing the same block.
     processed_blocks: LruCache<B256>,
+    /// Maximum time to wait for the engine to answer a single call.
+    engine_timeout: Duration,
+    /// Block the fork choice is pinned to.
+    head_override: HeadOverride,
+    /// Heads of the peers, the engine is steered towards the best one when behind.
+    peer_heads: PeerHeadTracker,
+    /// Ticks whenever the local head should be compared with the peer heads.
+    peer_head_sync: Interval,
 }
 
 impl<Provider> ImportService<Provider>
 where
-    Provider: BlockNumReader + Clone + 'static,
+    Provider: BlockNumReader + HeaderProvider<Header = Header> + Clone + 'static,
 {
     /// Create a new block import service
+    ///
+    /// Must be called from within a tokio runtime.
     pub fn new(
         consensus: Arc<ParliaConsensus<Provider>>,
         engine: BeaconConsensusEngineHandle<BscPayloadTypes>,
@@ -82,21 +199,107 @@ where
             engine,
             consensus,
             from_network,
+            from_rpc: None,
             to_network,
             pending_imports: FuturesUnordered::new(),
             processed_blocks: LruCache::new(LRU_PROCESSED_BLOCKS_SIZE),
+            engine_timeout: DEFAULT_ENGINE_CALL_TIMEOUT,
+            head_override: HeadOverride::default(),
+            peer_heads: PeerHeadTracker::default(),
+            peer_head_sync: tokio::time::interval(PEER_HEAD_SYNC_INTERVAL),
         }
     }
 
+    /// Sets the maximum time to wait for the engine to answer a single call.
+    ///
+    /// A call that times out completes without an outcome, so the peer isn't penalized for a
+    /// block our own engine failed to process in time.
+    pub const fn with_engine_timeout(mut self, engine_timeout: Duration) -> Self {
+        self.engine_timeout = engine_timeout;
+        self
+    }
+
+    /// Sets the override pinning the fork choice to a given block.
+    pub fn with_head_override(mut self, head_override: HeadOverride) -> Self {
+        self.head_override = head_override;
+        self
+    }
+
+    /// Sets the tracker of the peer heads the engine follows when the local chain falls behind.
+    pub fn with_peer_heads(mut self, peer_heads: PeerHeadTracker) -> Self {
+        self.peer_heads = peer_heads;
+        self
+    }
+
+    /// Accepts the blocks sent via the given RPC handle.
+    pub fn with_rpc_block_import(mut self, rpc_block_import: &RpcBlockImport) -> Self {
+        self.from_rpc = Some(rpc_block_import.connect());
+        self
+    }
+
+    /// Sends a fork choice update for the best peer head if the local chain is behind it.
+    ///
+    /// Peer heads are only recorded once the engine validated them, this catches up with
+    /// blocks whose own fork choice update failed or timed out. Nothing is sent while the fork
+    /// choice is pinned.
+    fn follow_peer_head(&self) -> Option<ImportFut> {
+        if self.head_override.get().is_some() {
+            return None;
+        }
+        let target = self.peer_heads.best_target()?;
+        let local = self.consensus.provider.best_block_number().ok()?;
+        if target.number <= local {
+            return None;
+        }
+
+        let engine = self.engine.clone();
+        let timeout = self.engine_timeout;
+        Some(Box::pin(async move {
+            debug!(
+                target: "net::block_import",
+                hash = %target.hash,
+                number = target.number,
+                local,
+                "Following the best peer head"
+            );
+            // zero hashes leave the local safe and finalized blocks untouched, a peer's head
+            // says nothing about what is safe
+            let state = ForkchoiceState {
+                head_block_hash: target.hash,
+                safe_block_hash: B256::ZERO,
+                finalized_block_hash: B256::ZERO,
+            };
+            let fcu = engine.fork_choice_updated(state, None, EngineApiMessageVersion::default());
+            if tokio::time::timeout(timeout, fcu).await.is_err() {
+                let hash = target.hash;
+                warn!(target: "net::block_import", %hash, ?timeout, "forkchoiceUpdated timed out");
+            }
+            // not tied to a block announcement, there is no outcome to report
+            None
+        }))
+    }
+
     /// Process a new payload and return the outcome
-    fn new_payload(&self, block: BlockMsg, peer_id: PeerId) -> ImportFut {
+    fn new_payload(
+        &self,
+        block: BlockMsg,
+        sealed_block: SealedBlock<BscBlock>,
+        peer_id: PeerId,
+    ) -> ImportFut {
         let engine = self.engine.clone();
+        let timeout = self.engine_timeout;
 
         Box::pin(async move {
-            let sealed_block = block.block.0.block.clone().seal();
             let payload = BscPayloadTypes::block_to_payload(sealed_block);
 
-            match engine.new_payload(payload).await {
+            let Ok(result) = tokio::time::timeout(timeout, engine.new_payload(payload)).await
+            else {
+                let hash = block.hash;
+                warn!(target: "net::block_import", %hash, ?timeout, "newPayload timed out");
+                return None;
+            };
+
+            match result {
                 Ok(payload_status) => match payload_status.status {
                     PayloadStatusEnum::Valid => {
                         Outcome { peer: peer_id, result: Ok(BlockValidation::ValidBlock { block }) }
@@ -114,18 +317,33 @@ where
         })
     }
 
-    /// Process a forkchoice update and return the outcome
-    fn update_fork_choice(&self, block: BlockMsg, peer_id: PeerId) -> ImportFut {
+    /// Process a forkchoice update for the block with the given hash and return the outcome
+    fn update_fork_choice(&self, block: BlockMsg, hash: B256, peer_id: PeerId) -> ImportFut {
         let engine = self.engine.clone();
         let consensus = self.consensus.clone();
-        let sealed_block = block.block.0.block.clone().seal();
-        let hash = sealed_block.hash();
-        let number = sealed_block.number();
+        let timeout = self.engine_timeout;
+        let number = block.block.0.block.header.number;
+        // the difficulty is covered by the seal, unlike the total difficulty the peer announced
+        let difficulty = block.block.0.block.header.difficulty;
+        let pinned = self.head_override.get();
 
         Box::pin(async move {
-            let (head_block_hash, current_hash) = match consensus.canonical_head(hash, number) {
-                Ok(hash) => hash,
-                Err(_) => return None,
+            let head_block_hash = match pinned {
+                Some(pinned) if pinned == hash => pinned,
+                Some(pinned) => {
+                    debug!(
+                        target: "net::block_import",
+                        %hash,
+                        number,
+                        %pinned,
+                        "Fork choice update suppressed by head override"
+                    );
+                    return None;
+                }
+                None => match consensus.canonical_head(hash, number, difficulty) {
+                    Ok((head_block_hash, _)) => head_block_hash,
+                    Err(_) => return None,
+                },
             };
 
             let state = ForkchoiceState {
@@ -134,8 +352,13 @@ where
                 finalized_block_hash: head_block_hash,
             };
 
-            match engine.fork_choice_updated(state, None, EngineApiMessageVersion::default()).await
-            {
+            let fcu = engine.fork_choice_updated(state, None, EngineApiMessageVersion::default());
+            let Ok(result) = tokio::time::timeout(timeout, fcu).await else {
+                warn!(target: "net::block_import", %hash, ?timeout, "forkchoiceUpdated timed out");
+                return None;
+            };
+
+            match result {
                 Ok(response) => match response.payload_status.status {
                     PayloadStatusEnum::Valid => {
                         Outcome { peer: peer_id, result: Ok(BlockValidation::ValidBlock { block }) }
@@ -159,17 +382,57 @@ where
             return;
         }
 
-        let payload_fut = self.new_payload(block.clone(), peer_id);
+        // the payload sent to the engine can't carry ommers, reject such blocks right away
+        let inner = &block.block.0.block;
+        if let Err(err) = validate_no_ommers(&inner.header, &inner.body.inner.ommers) {
+            let outcome = Outcome { peer: peer_id, result: Err(BlockImportError::Consensus(err)) };
+            self.pending_imports.push(Box::pin(async move { Some(outcome) }));
+            return;
+        }
+
+        // the block is copied and hashed once, the payload takes the copy and the fork choice
+        // only needs the hash
+        let sealed_block = block.block.0.block.clone().seal();
+        let hash = sealed_block.hash();
+
+        let payload_fut = self.new_payload(block.clone(), sealed_block, peer_id);
         self.pending_imports.push(payload_fut);
 
-        let fcu_fut = self.update_fork_choice(block, peer_id);
+        let fcu_fut = self.update_fork_choice(block, hash, peer_id);
         self.pending_imports.push(fcu_fut);
     }
+
+    /// Imports a block received via RPC, the status goes to the caller instead of the network.
+    fn on_rpc_block(&mut self, block: BlockMsg, status_tx: oneshot::Sender<ImportStatus>) {
+        let inner = &block.block.0.block;
+        if let Err(err) = validate_no_ommers(&inner.header, &inner.body.inner.ommers) {
+            let _ = status_tx.send(ImportStatus::Invalid { error: err.to_string() });
+            return;
+        }
+
+        // the block has no origin peer, the id only ends up in the outcomes consumed here
+        let sealed_block = block.block.0.block.clone().seal();
+        let hash = sealed_block.hash();
+        let payload_fut = self.new_payload(block.clone(), sealed_block, PeerId::ZERO);
+        let fcu_fut = self.update_fork_choice(block, hash, PeerId::ZERO);
+
+        self.pending_imports.push(Box::pin(async move {
+            let mut status = ImportStatus::from_outcome(payload_fut.await);
+            if status == ImportStatus::Valid {
+                // a suppressed fork choice update leaves the block valid
+                if let Some(outcome) = fcu_fut.await {
+                    status = ImportStatus::from_outcome(Some(outcome));
+                }
+            }
+            let _ = status_tx.send(status);
+            None
+        }));
+    }
 }
 
 impl<Provider> Future for ImportService<Provider>
 where
-    Provider: BlockNumReader + BlockHashReader + Clone + 'static + Unpin,
+    Provider: BlockNumReader + HeaderProvider<Header = Header> + Clone + 'static + Unpin,
 {
     type Output = Result<(), Box<dyn std::error::Error>>;
 
@@ -181,6 +444,20 @@ where
             this.on_new_block(block, peer_id);
         }
 
+        // Receive blocks imported via RPC
+        while let Some(Poll::Ready(Some((block, status_tx)))) =
+            this.from_rpc.as_mut().map(|from_rpc| from_rpc.poll_recv(cx))
+        {
+            this.on_rpc_block(block, status_tx);
+        }
+
+        // Catch up with the peers if the fork choice fell behind them
+        while this.peer_head_sync.poll_tick(cx).is_ready() {
+            if let Some(fut) = this.follow_peer_head() {
+                this.pending_imports.push(fut);
+            }
+        }
+
         // Process completed imports and send events to network
         while let Poll::Ready(Some(outcome)) = this.pending_imports.poll_next_unpin(cx) {
             if let Some(outcome) = outcome {
@@ -203,14 +480,18 @@ mod tests {
     use crate::chainspec::bsc::bsc_mainnet;
 
     use super::*;
+    use crate::{
+        consensus::{DIFF_INTURN, DIFF_NOTURN},
+        node::network::peer_heads::PeerHead,
+    };
     use alloy_primitives::{B256, U128};
     use alloy_rpc_types::engine::PayloadStatus;
-    use reth_chainspec::ChainInfo;
+    use reth::consensus::ConsensusError;
     use reth_engine_primitives::{BeaconEngineMessage, OnForkChoiceUpdated};
     use reth_eth_wire::NewBlock;
     use reth_node_ethereum::EthEngineTypes;
     use reth_primitives::Block;
-    use reth_provider::ProviderError;
+    use reth_provider::test_utils::MockEthProvider;
     use std::{
         sync::Arc,
         task::{Context, Poll},
@@ -309,39 +590,230 @@ mod tests {
         }
     }
 
-    #[derive(Clone)]
-    struct MockProvider;
+    #[tokio::test]
+    async fn engine_call_times_out() {
+        let consensus = Arc::new(ParliaConsensus::new(provider(Header::default())));
+        let (to_engine, mut from_engine) = mpsc::unbounded_channel();
+        let engine_handle = BeaconConsensusEngineHandle::new(to_engine);
+
+        // Hold on to the requests without answering to simulate a hanging engine
+        tokio::spawn(async move {
+            let mut requests = Vec::new();
+            while let Some(message) = from_engine.recv().await {
+                requests.push(message);
+            }
+        });
+
+        let (_to_import, from_network) = mpsc::unbounded_channel();
+        let (to_network, _import_outcome) = mpsc::unbounded_channel();
+        let service = ImportService::new(consensus, engine_handle, from_network, to_network)
+            .with_engine_timeout(Duration::from_millis(50));
+
+        let block = create_test_block();
+        let peer_id = PeerId::random();
+        let imports = tokio::time::timeout(Duration::from_secs(5), async {
+            (
+                service.new_payload(block.clone(), sealed(&block), peer_id).await,
+                service.update_fork_choice(block.clone(), block.hash, peer_id).await,
+            )
+        });
+        let (new_payload, fcu) = imports.await.expect("engine calls should time out");
+        assert!(new_payload.is_none());
+        assert!(fcu.is_none());
+    }
 
-    impl BlockNumReader for MockProvider {
-        fn chain_info(&self) -> Result<ChainInfo, ProviderError> {
-            unimplemented!()
-        }
+    #[tokio::test]
+    async fn rejects_block_with_ommers() {
+        let mut fixture = TestFixture::new(EngineResponses::both_valid()).await;
 
-        fn best_block_number(&self) -> Result<u64, ProviderError> {
-            Ok(0)
-        }
+        let mut block = create_test_block();
+        let mut new_block = (*block.block).clone();
+        new_block.0.block.body.inner.ommers.push(Header::default());
+        block.block = Arc::new(new_block);
+        fixture.handle.send_block(block, PeerId::random()).unwrap();
 
-        fn last_block_number(&self) -> Result<u64, ProviderError> {
-            Ok(0)
-        }
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let outcome = loop {
+            match fixture.handle.poll_outcome(&mut cx) {
+                Poll::Ready(outcome) => break outcome.unwrap(),
+                Poll::Pending => tokio::task::yield_now().await,
+            }
+        };
+        assert!(matches!(
+            outcome,
+            BlockImportEvent::Outcome(BlockImportOutcome {
+                peer: _,
+                result: Err(BlockImportError::Consensus(ConsensusError::BodyOmmersHashDiff(_)))
+            })
+        ));
 
-        fn block_number(&self, _hash: B256) -> Result<Option<u64>, ProviderError> {
-            Ok(None)
-        }
+        // the block never reaches the engine, so no further outcome follows
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!matches!(fixture.handle.poll_outcome(&mut cx), Poll::Ready(Some(_))));
     }
 
-    impl BlockHashReader for MockProvider {
-        fn block_hash(&self, _number: u64) -> Result<Option<B256>, ProviderError> {
-            Ok(Some(B256::ZERO))
-        }
+    #[tokio::test]
+    async fn head_override_pins_fork_choice() {
+        let consensus = Arc::new(ParliaConsensus::new(provider(Header::default())));
+        let (to_engine, mut from_engine) = mpsc::unbounded_channel();
+        let engine_handle = BeaconConsensusEngineHandle::new(to_engine);
+
+        // Record the calls reaching the engine, `None` for newPayload and the head for FCU
+        let (to_calls, mut calls) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = from_engine.recv().await {
+                let status = PayloadStatus::new(PayloadStatusEnum::Valid, None);
+                match message {
+                    BeaconEngineMessage::NewPayload { tx, .. } => {
+                        to_calls.send(None).unwrap();
+                        tx.send(Ok(status)).unwrap();
+                    }
+                    BeaconEngineMessage::ForkchoiceUpdated { state, tx, .. } => {
+                        to_calls.send(Some(state.head_block_hash)).unwrap();
+                        tx.send(Ok(OnForkChoiceUpdated::valid(status))).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let (_to_import, from_network) = mpsc::unbounded_channel();
+        let (to_network, _import_outcome) = mpsc::unbounded_channel();
+        let head_override = HeadOverride::default();
+        let service = ImportService::new(consensus, engine_handle, from_network, to_network)
+            .with_head_override(head_override.clone());
+
+        let block = |number| {
+            let mut block = create_test_block();
+            let mut new_block = (*block.block).clone();
+            new_block.0.block.header.number = number;
+            block.hash = new_block.0.block.header.hash_slow();
+            block.block = Arc::new(new_block);
+            block
+        };
+        let (pinned, above) = (block(1), block(2));
+        let peer_id = PeerId::random();
+
+        head_override.set(Some(pinned.hash));
+        assert!(service.update_fork_choice(pinned.clone(), pinned.hash, peer_id).await.is_some());
+        assert_eq!(calls.recv().await, Some(Some(pinned.hash)));
+
+        // blocks above the pin are still validated, but the fork choice stays put
+        assert!(service.new_payload(above.clone(), sealed(&above), peer_id).await.is_some());
+        assert!(service.update_fork_choice(above.clone(), above.hash, peer_id).await.is_none());
+        assert_eq!(calls.recv().await, Some(None));
+        assert!(calls.try_recv().is_err());
+
+        // releasing the pin resumes the fork choice updates
+        head_override.set(None);
+        assert!(service.update_fork_choice(above.clone(), above.hash, peer_id).await.is_some());
+        assert_eq!(calls.recv().await, Some(Some(above.hash)));
+    }
 
-        fn canonical_hashes_range(
-            &self,
-            _start: u64,
-            _end: u64,
-        ) -> Result<Vec<B256>, ProviderError> {
-            Ok(vec![])
-        }
+    #[tokio::test]
+    async fn follows_best_peer_head() {
+        let consensus = Arc::new(ParliaConsensus::new(provider(Header::default())));
+        let (to_engine, mut from_engine) = mpsc::unbounded_channel();
+        let engine_handle = BeaconConsensusEngineHandle::new(to_engine);
+
+        // Record the fork choice updates reaching the engine
+        let (to_states, mut states) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = from_engine.recv().await {
+                if let BeaconEngineMessage::ForkchoiceUpdated { state, tx, .. } = message {
+                    to_states.send(state).unwrap();
+                    let status = PayloadStatus::new(PayloadStatusEnum::Valid, None);
+                    tx.send(Ok(OnForkChoiceUpdated::valid(status))).unwrap();
+                }
+            }
+        });
+
+        let (_to_import, from_network) = mpsc::unbounded_channel();
+        let (to_network, _import_outcome) = mpsc::unbounded_channel();
+        let (peer_heads, head_override) = (PeerHeadTracker::default(), HeadOverride::default());
+        let service = ImportService::new(consensus, engine_handle, from_network, to_network)
+            .with_peer_heads(peer_heads.clone())
+            .with_head_override(head_override.clone());
+        let head = |number| PeerHead {
+            number,
+            hash: B256::with_last_byte(number as u8),
+            difficulty: DIFF_INTURN,
+        };
+
+        // nothing to follow without peers, or while they are level with the local head
+        assert!(service.follow_peer_head().is_none());
+        peer_heads.on_new_block(PeerId::random(), head(0));
+        assert!(service.follow_peer_head().is_none());
+
+        // a peer ahead is followed, unless the fork choice is pinned
+        peer_heads.on_new_block(PeerId::random(), head(5));
+        head_override.set(Some(B256::ZERO));
+        assert!(service.follow_peer_head().is_none());
+        head_override.set(None);
+        assert!(service.follow_peer_head().unwrap().await.is_none());
+        // only the head moves, the local safe and finalized blocks are kept
+        let state = states.recv().await.unwrap();
+        assert_eq!(
+            (state.head_block_hash, state.safe_block_hash, state.finalized_block_hash),
+            (head(5).hash, B256::ZERO, B256::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn out_of_turn_block_loses_despite_higher_td() {
+        let block = |difficulty, td: u64, salt: u8| {
+            let mut block = create_test_block();
+            let mut new_block = (*block.block).clone();
+            new_block.0.block.header.number = 10;
+            new_block.0.block.header.difficulty = difficulty;
+            new_block.0.block.header.mix_hash = B256::with_last_byte(salt);
+            new_block.0.td = U128::from(td);
+            block.hash = new_block.0.block.header.hash_slow();
+            block.block = Arc::new(new_block);
+            block
+        };
+        let in_turn = block(DIFF_INTURN, 100, 0);
+        // a lower hash would win the tie-break between blocks of the same difficulty
+        let out_of_turn = (1..=u8::MAX)
+            .map(|salt| block(DIFF_NOTURN, 1_000_000, salt))
+            .find(|block| block.hash < in_turn.hash)
+            .unwrap();
+
+        let consensus =
+            Arc::new(ParliaConsensus::new(provider(in_turn.block.0.block.header.clone())));
+        let (to_engine, mut from_engine) = mpsc::unbounded_channel();
+        let engine_handle = BeaconConsensusEngineHandle::new(to_engine);
+
+        // Record the heads of the fork choice updates reaching the engine
+        let (to_heads, mut heads) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = from_engine.recv().await {
+                if let BeaconEngineMessage::ForkchoiceUpdated { state, tx, .. } = message {
+                    to_heads.send(state.head_block_hash).unwrap();
+                    let status = PayloadStatus::new(PayloadStatusEnum::Valid, None);
+                    tx.send(Ok(OnForkChoiceUpdated::valid(status))).unwrap();
+                }
+            }
+        });
+
+        let (_to_import, from_network) = mpsc::unbounded_channel();
+        let (to_network, _import_outcome) = mpsc::unbounded_channel();
+        let service = ImportService::new(consensus, engine_handle, from_network, to_network);
+        let peer_id = PeerId::random();
+
+        // the out-of-turn block announces the higher total difficulty, but the in-turn head stays,
+        // its difficulty is read from the canonical header, as after a restart
+        let fcu = service.update_fork_choice(out_of_turn.clone(), out_of_turn.hash, peer_id);
+        assert!(fcu.await.is_some());
+        assert_eq!(heads.recv().await, Some(in_turn.hash));
+    }
+
+    /// Provider whose canonical chain ends at the given head.
+    fn provider(head: Header) -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        provider.add_header(head.hash_slow(), head);
+        provider
     }
 
     /// Response configuration for engine messages
@@ -378,7 +850,7 @@ mod tests {
     impl TestFixture {
         /// Create a new test fixture with the given engine responses
         async fn new(responses: EngineResponses) -> Self {
-            let consensus = Arc::new(ParliaConsensus { provider: MockProvider });
+            let consensus = Arc::new(ParliaConsensus::new(provider(Header::default())));
             let (to_engine, from_engine) = mpsc::unbounded_channel();
             let engine_handle = BeaconConsensusEngineHandle::new(to_engine);
 
@@ -428,6 +900,11 @@ mod tests {
         }
     }
 
+    /// Seals the block of a test block message
+    fn sealed(block: &BlockMsg) -> SealedBlock<BscBlock> {
+        block.block.0.block.clone().seal()
+    }
+
     /// Creates a test block message
     fn create_test_block() -> NewBlockMessage<BscNewBlock> {
         let block = BscBlock {
@@ -11,7 +11,13 @@ pub fn bsc_testnet_nodes() -> Vec<NodeRecord> {
 
 /// Parses all the nodes
 pub fn parse_nodes(nodes: impl IntoIterator<Item = impl AsRef<str>>) -> Vec<NodeRecord> {
-    nodes.into_iter().map(|s| s.as_ref().parse().unwrap()).collect()
+    nodes
+        .into_iter()
+        .map(|s| {
+            let s = s.as_ref();
+            s.parse().unwrap_or_else(|err| panic!("invalid bootnode {s}: {err}"))
+        })
+        .collect()
 }
 
 /// Bsc mainnet boot nodes.
@@ -31,3 +37,30 @@ pub static BSC_TESTNET_BOOTNODES: &[&str] = &[
     "enode://ecd664250ca19b1074dcfbfb48576a487cc18d052064222a363adacd2650f8e08fb3db9de7a7aecb48afa410eaeb3285e92e516ead01fb62598553aed91ee15e@3.209.122.123:30311",
     "enode://665cf77ca26a8421cfe61a52ac312958308d4912e78ce8e0f61d6902e4494d4cc38f9b0dd1b23a427a7a5734e27e5d9729231426b06bb9c73b56a142f83f6b68@52.72.123.113:30311",
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builtin_bootnodes_parse() {
+        for nodes in [BSC_MAINNET_BOOTNODES, BSC_TESTNET_BOOTNODES] {
+            assert!(!nodes.is_empty());
+
+            let mut ids = HashSet::new();
+            for node in nodes {
+                let record = node
+                    .parse::<NodeRecord>()
+                    .unwrap_or_else(|err| panic!("invalid bootnode {node}: {err}"));
+                assert!(!record.address.is_unspecified(), "unroutable bootnode {node}");
+                assert!(!record.address.is_loopback(), "unroutable bootnode {node}");
+                assert_ne!(record.tcp_port, 0, "missing tcp port in bootnode {node}");
+                assert!(ids.insert(record.id), "duplicate bootnode {node}");
+            }
+        }
+
+        assert_eq!(bsc_mainnet_nodes().len(), BSC_MAINNET_BOOTNODES.len());
+        assert_eq!(bsc_testnet_nodes().len(), BSC_TESTNET_BOOTNODES.len());
+    }
+}
@@ -0,0 +1,125 @@
+//! Hand-off of the beacon engine handle from the node launcher to the network components.
+//!
+//! The network is built before the engine is launched, so the block import task has to wait for
+//! the launcher to publish the engine handle once the node is up. The launch may take arbitrarily
+//! long, the import task therefore waits without a deadline.
+
+use crate::node::engine_api::payload::BscPayloadTypes;
+use reth_engine_primitives::BeaconConsensusEngineHandle;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Errors returned while waiting for the engine handle.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EngineHandleError {
+    /// The sender was dropped without publishing a handle, e.g. because the launch failed.
+    #[error("engine handle sender dropped before the node was launched")]
+    SenderDropped,
+    /// No handle was published within the given duration.
+    #[error("engine handle not received within {0:?}")]
+    Timeout(Duration),
+}
+
+/// Creates a new engine handle channel.
+pub fn channel() -> (EngineHandleSender, EngineHandleReceiver) {
+    let (tx, rx) = watch::channel(None);
+    (EngineHandleSender(tx), EngineHandleReceiver(rx))
+}
+
+/// Publishes the engine handle once the node has been launched.
+#[derive(Debug)]
+pub struct EngineHandleSender(watch::Sender<Option<BeaconConsensusEngineHandle<BscPayloadTypes>>>);
+
+impl EngineHandleSender {
+    /// Publishes the engine handle to all current and future receivers.
+    pub fn send(&self, handle: BeaconConsensusEngineHandle<BscPayloadTypes>) {
+        self.0.send_replace(Some(handle));
+    }
+}
+
+/// Receives the engine handle published by the launcher.
+///
+/// The receiver can be cloned freely, so components may be constructed any number of times and
+/// before or after the handle has been sent.
+#[derive(Debug, Clone)]
+pub struct EngineHandleReceiver(
+    watch::Receiver<Option<BeaconConsensusEngineHandle<BscPayloadTypes>>>,
+);
+
+impl EngineHandleReceiver {
+    /// Waits until the engine handle has been published.
+    ///
+    /// Returns immediately if the handle was already sent. Fails if the sender is dropped without
+    /// sending.
+    pub async fn wait(
+        &mut self,
+    ) -> Result<BeaconConsensusEngineHandle<BscPayloadTypes>, EngineHandleError> {
+        let handle =
+            self.0.wait_for(Option::is_some).await.map_err(|_| EngineHandleError::SenderDropped)?;
+        Ok(handle.clone().expect("checked by wait_for"))
+    }
+
+    /// Waits until the engine handle has been published, for at most `timeout`.
+    pub async fn wait_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<BeaconConsensusEngineHandle<BscPayloadTypes>, EngineHandleError> {
+        tokio::time::timeout(timeout, self.wait())
+            .await
+            .map_err(|_| EngineHandleError::Timeout(timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn engine_handle() -> BeaconConsensusEngineHandle<BscPayloadTypes> {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        BeaconConsensusEngineHandle::new(tx)
+    }
+
+    #[tokio::test]
+    async fn test_sender_dropped() {
+        let (tx, mut rx) = channel();
+        drop(tx);
+        assert_eq!(
+            rx.wait_timeout(Duration::from_secs(1)).await.unwrap_err(),
+            EngineHandleError::SenderDropped
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delayed_send() {
+        let (tx, mut rx) = channel();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx.send(engine_handle());
+        });
+        assert!(rx.wait_timeout(Duration::from_secs(5)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let (_tx, mut rx) = channel();
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            rx.wait_timeout(timeout).await.unwrap_err(),
+            EngineHandleError::Timeout(timeout)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multiple_receivers() {
+        let (tx, rx) = channel();
+        let mut first = rx.clone();
+        tx.send(engine_handle());
+        drop(tx);
+
+        // receivers created before and after the send, and after the sender is gone
+        let mut second = rx.clone();
+        assert!(first.wait_timeout(Duration::from_secs(1)).await.is_ok());
+        assert!(second.wait_timeout(Duration::from_secs(1)).await.is_ok());
+    }
+}
@@ -2,14 +2,24 @@
 use crate::{
     consensus::ParliaConsensus,
     node::{
-        engine_api::payload::BscPayloadTypes,
-        network::block_import::{handle::ImportHandle, service::ImportService, BscBlockImport},
+        consensus::BscConsensus,
+        network::{
+            block_import::{
+                fetcher::{Announcement, BlockFetcher},
+                handle::ImportHandle,
+                service::{HeadOverride, ImportService, IncomingBlock, RpcBlockImport},
+                BscBlockImport,
+            },
+            engine_handle::EngineHandleReceiver,
+            peer_heads::PeerHeadTracker,
+        },
         primitives::{BscBlobTransactionSidecar, BscPrimitives},
         BscNode,
     },
     BscBlock,
 };
 use alloy_rlp::{Decodable, Encodable};
+use futures::StreamExt;
 use handshake::BscHandshake;
 use reth::{
     api::{FullNodeTypes, TxTy},
@@ -18,19 +28,24 @@ use reth::{
 };
 use reth_chainspec::EthChainSpec;
 use reth_discv4::Discv4Config;
-use reth_engine_primitives::BeaconConsensusEngineHandle;
 use reth_eth_wire::{BasicNetworkPrimitives, NewBlock, NewBlockPayload};
 use reth_ethereum_primitives::PooledTransactionVariant;
 use reth_network::{NetworkConfig, NetworkHandle, NetworkManager};
-use reth_network_api::PeersInfo;
+use reth_network_api::{
+    events::{NetworkEvent, PeerEvent},
+    BlockDownloaderProvider, NetworkEventListenerProvider, PeersInfo,
+};
+use reth_network_p2p::full_block::FullBlockClient;
 use std::{sync::Arc, time::Duration};
-use tokio::sync::{mpsc, oneshot, Mutex};
-use tracing::info;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::{error, info, warn};
 
 pub mod block_import;
 pub mod bootnodes;
+pub mod engine_handle;
 pub mod handshake;
-pub(crate) mod upgrade_status;
+pub mod peer_heads;
+pub mod upgrade_status;
 /// BSC `NewBlock` message value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BscNewBlock(pub NewBlock<BscBlock>);
@@ -138,14 +153,99 @@ impl NewBlockPayload for BscNewBlock {
 pub type BscNetworkPrimitives =
     BasicNetworkPrimitives<BscPrimitives, PooledTransactionVariant, BscNewBlock>;
 
+/// Default minimum number of peers a BSC validator should be able to keep.
+pub const DEFAULT_MIN_VALIDATOR_PEERS: usize = 20;
+
+/// BSC specific networking arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::Args)]
+pub struct BscNetworkArgs {
+    /// Minimum number of peers required by a validator, a warning is emitted on startup if the
+    /// configured peer limit is lower.
+    #[arg(long = "bsc.min-validator-peers", default_value_t = DEFAULT_MIN_VALIDATOR_PEERS)]
+    pub min_validator_peers: usize,
+}
+
+impl Default for BscNetworkArgs {
+    fn default() -> Self {
+        Self { min_validator_peers: DEFAULT_MIN_VALIDATOR_PEERS }
+    }
+}
+
+/// Errors of the bsc network configuration.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigError {
+    /// The minimum number of peers exceeds the maximum number of peers.
+    #[error("minimum peers {min} exceeds maximum peers {max}")]
+    InvalidPeerConfig {
+        /// Configured maximum number of peers.
+        max: usize,
+        /// Configured minimum number of peers.
+        min: usize,
+    },
+}
+
+/// Channels of the block fetcher, which is started once the network is up.
+type FetcherChannels = (UnboundedReceiver<Announcement>, UnboundedSender<IncomingBlock>);
+
 /// A basic bsc network builder.
 #[derive(Debug)]
 pub struct BscNetworkBuilder {
-    pub(crate) engine_handle_rx:
-        Arc<Mutex<Option<oneshot::Receiver<BeaconConsensusEngineHandle<BscPayloadTypes>>>>>,
+    /// Receives the engine handle once the node has been launched.
+    pub(crate) engine_handle_rx: EngineHandleReceiver,
+    /// Minimum number of peers required by a validator.
+    min_peers: usize,
+    /// Records the heads announced by peers.
+    peer_heads: PeerHeadTracker,
+    /// Pins the fork choice of the block import, initialized from `--debug.tip`.
+    head_override: HeadOverride,
+    /// Feeds blocks received via RPC into the block import.
+    rpc_block_import: RpcBlockImport,
 }
 
 impl BscNetworkBuilder {
+    /// Create a new instance of [`BscNetworkBuilder`]
+    pub(crate) fn new(engine_handle_rx: EngineHandleReceiver) -> Self {
+        Self {
+            engine_handle_rx,
+            min_peers: DEFAULT_MIN_VALIDATOR_PEERS,
+            peer_heads: PeerHeadTracker::default(),
+            head_override: HeadOverride::default(),
+            rpc_block_import: RpcBlockImport::default(),
+        }
+    }
+
+    /// Sets the tracker recording the heads announced by peers.
+    pub fn with_peer_heads(mut self, peer_heads: PeerHeadTracker) -> Self {
+        self.peer_heads = peer_heads;
+        self
+    }
+
+    /// Sets the override pinning the fork choice of the block import.
+    pub fn with_head_override(mut self, head_override: HeadOverride) -> Self {
+        self.head_override = head_override;
+        self
+    }
+
+    /// Sets the handle feeding blocks received via RPC into the block import.
+    pub fn with_rpc_block_import(mut self, rpc_block_import: RpcBlockImport) -> Self {
+        self.rpc_block_import = rpc_block_import;
+        self
+    }
+
+    /// Sets the minimum number of peers required by a validator.
+    pub const fn with_min_peers(mut self, min_peers: usize) -> Self {
+        self.min_peers = min_peers;
+        self
+    }
+
+    /// Validates the peer limits against the BSC requirements.
+    pub const fn validate_peer_config(max: usize, min: usize) -> Result<(), ConfigError> {
+        if min > max {
+            return Err(ConfigError::InvalidPeerConfig { max, min })
+        }
+        Ok(())
+    }
+
     /// Returns the [`NetworkConfig`] that contains the settings to launch the p2p network.
     ///
     /// This applies the configured [`BscNetworkBuilder`] settings.
@@ -156,7 +256,20 @@ impl BscNetworkBuilder {
     where
         Node: FullNodeTypes<Types = BscNode>,
     {
-        let Self { engine_handle_rx } = self;
+        Ok(self.network_config_with_fetcher(ctx)?.0)
+    }
+
+    /// Returns the [`NetworkConfig`] together with the channels of the fetcher of the blocks
+    /// announced by hash.
+    fn network_config_with_fetcher<Node>(
+        self,
+        ctx: &BuilderContext<Node>,
+    ) -> eyre::Result<(NetworkConfig<Node::Provider, BscNetworkPrimitives>, FetcherChannels)>
+    where
+        Node: FullNodeTypes<Types = BscNode>,
+    {
+        let Self { mut engine_handle_rx, min_peers, peer_heads, head_override, rpc_block_import } =
+            self;
 
         let network_builder = ctx.network_config_builder()?;
         let mut discv4 = Discv4Config::builder();
@@ -168,33 +281,52 @@ impl BscNetworkBuilder {
 
         let (to_import, from_network) = mpsc::unbounded_channel();
         let (to_network, import_outcome) = mpsc::unbounded_channel();
+        let (to_fetcher, announcements) = mpsc::unbounded_channel();
+        let fetcher = (announcements, to_import.clone());
 
         let handle = ImportHandle::new(to_import, import_outcome);
-        let consensus = Arc::new(ParliaConsensus { provider: ctx.provider().clone() });
+        let consensus = Arc::new(ParliaConsensus::new(ctx.provider().clone()));
+        if let Some(tip) = ctx.config().debug.tip {
+            info!(target: "reth::cli", %tip, "Pinning the fork choice to the debug tip");
+            head_override.set(Some(tip));
+        }
 
+        let import_peer_heads = peer_heads.clone();
         ctx.task_executor().spawn_critical("block import", async move {
-            let handle = engine_handle_rx
-                .lock()
-                .await
-                .take()
-                .expect("node should only be launched once")
-                .await
-                .unwrap();
-
-            ImportService::new(consensus, handle, from_network, to_network).await.unwrap();
+            // without the handle the node would silently stop following the chain, the sender
+            // is only dropped unsent if the launch failed
+            let handle =
+                engine_handle_rx.wait().await.expect("engine handle is sent once the node is up");
+
+            let service = ImportService::new(consensus, handle, from_network, to_network)
+                .with_head_override(head_override)
+                .with_peer_heads(import_peer_heads)
+                .with_rpc_block_import(&rpc_block_import);
+            if let Err(err) = service.await {
+                error!(target: "reth::cli", %err, "Block import service failed");
+            }
         });
 
         let network_builder = network_builder
             .boot_nodes(ctx.chain_spec().bootnodes().unwrap_or_default())
             .set_head(ctx.chain_spec().head())
             .with_pow()
-            .block_import(Box::new(BscBlockImport::new(handle)))
+            .block_import(Box::new(
+                BscBlockImport::new(handle, peer_heads).with_block_fetcher(to_fetcher),
+            ))
             .discovery(discv4)
             .eth_rlpx_handshake(Arc::new(BscHandshake::default()));
 
         let network_config = ctx.build_network_config(network_builder);
 
-        Ok(network_config)
+        // the peer limits are taken from `--max-outbound-peers` and `--max-inbound-peers`
+        let connections = &network_config.peers_config.connection_info;
+        let max_peers = connections.max_outbound + connections.max_inbound;
+        if let Err(err) = Self::validate_peer_config(max_peers, min_peers) {
+            warn!(target: "reth::cli", %err, "Peer limit is below the BSC validator requirement");
+        }
+
+        Ok((network_config, fetcher))
     }
 }
 
@@ -216,11 +348,85 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<Self::Network> {
-        let network_config = self.network_config(ctx)?;
+        let peer_heads = self.peer_heads.clone();
+        let (network_config, (announcements, to_import)) = self.network_config_with_fetcher(ctx)?;
         let network = NetworkManager::builder(network_config).await?;
         let handle = ctx.start_network(network, pool);
+
+        // blocks announced by hash are downloaded from whichever peer serves them, the bodies are
+        // checked against the headers before they reach the block import
+        let consensus = Arc::new(BscConsensus::new(ctx.chain_spec()));
+        let downloader = FullBlockClient::new(handle.fetch_client().await?, consensus);
+        let fetcher = BlockFetcher::new(downloader, announcements, to_import);
+        ctx.task_executor().spawn_critical("block fetcher", fetcher);
+
+        // the head of a disconnected peer is no sync target anymore
+        let mut events = handle.event_listener();
+        ctx.task_executor().spawn(async move {
+            while let Some(event) = events.next().await {
+                if let NetworkEvent::Peer(PeerEvent::SessionClosed { peer_id, .. }) = event {
+                    peer_heads.remove_peer(&peer_id);
+                }
+            }
+        });
         info!(target: "reth::cli", enode=%handle.local_node_record(), "P2P networking initialized");
 
         Ok(handle)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chainspec::{bsc::bsc_mainnet, BscChainSpec};
+    use reth_network::{config::rng_secret_key, NetworkConfigBuilder};
+    use reth_provider::noop::NoopProvider;
+
+    #[test]
+    fn test_validate_peer_config() {
+        assert!(BscNetworkBuilder::validate_peer_config(100, DEFAULT_MIN_VALIDATOR_PEERS).is_ok());
+        assert!(BscNetworkBuilder::validate_peer_config(20, 20).is_ok());
+        assert_eq!(
+            BscNetworkBuilder::validate_peer_config(10, 20),
+            Err(ConfigError::InvalidPeerConfig { max: 10, min: 20 })
+        );
+    }
+
+    #[test]
+    fn test_builder_peer_limits() {
+        let builder = BscNetworkBuilder::new(engine_handle::channel().1);
+        assert_eq!(builder.min_peers, DEFAULT_MIN_VALIDATOR_PEERS);
+
+        let builder = builder.with_min_peers(15);
+        assert_eq!(builder.min_peers, 15);
+    }
+
+    #[test]
+    fn test_advertised_fork_id() {
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let head = chain_spec.head();
+
+        let config = NetworkConfigBuilder::<BscNetworkPrimitives>::new(rng_secret_key())
+            .set_head(head)
+            .build(NoopProvider::<_, BscPrimitives>::new(chain_spec.clone()));
+
+        let fork_id = chain_spec.fork_filter(head).current();
+        assert_eq!(config.status.forkid, fork_id);
+        assert_eq!(config.fork_filter.current(), fork_id);
+        assert_eq!(chain_spec.fork_id(&head), fork_id);
+    }
+
+    #[tokio::test]
+    async fn test_builder_rebuilt_from_same_node() {
+        let (node, engine_handle_tx) = BscNode::new();
+        let first = BscNetworkBuilder::new(node.engine_handle_rx.clone());
+        let second = BscNetworkBuilder::new(node.engine_handle_rx.clone());
+
+        let (to_engine, _from_node) = mpsc::unbounded_channel();
+        engine_handle_tx.send(reth_engine_primitives::BeaconConsensusEngineHandle::new(to_engine));
+
+        for mut builder in [first, second] {
+            assert!(builder.engine_handle_rx.wait_timeout(Duration::from_secs(1)).await.is_ok());
+        }
+    }
+}
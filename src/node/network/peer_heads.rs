@@ -0,0 +1,178 @@
+//! Tracking of the chain heads announced by peers.
+//!
+//! BSC has no consensus layer handing out a sync target, the best available hint is what peers
+//! announce via `NewBlock` messages. A peer's head is recorded once the engine validated the
+//! announced block and dropped when the peer disconnects, the block import steers the engine
+//! towards [`PeerHeadTracker::best_target`] when the local chain falls behind.
+
+use alloy_primitives::{B256, U256};
+use parking_lot::Mutex;
+use reth_network_api::PeerId;
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+/// Maximum number of peers whose announced head is tracked.
+const MAX_TRACKED_PEERS: usize = 512;
+
+/// Announced heads further than this above the median head are ignored when selecting a target.
+pub const MAX_HEAD_DISTANCE_FROM_MEDIAN: u64 = 1_024;
+
+/// Head announced by a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerHead {
+    /// Number of the announced block.
+    pub number: u64,
+    /// Hash of the announced block.
+    pub hash: B256,
+    /// Difficulty of the sealed header, whether the block was sealed in turn.
+    pub difficulty: U256,
+}
+
+/// Keeps the highest head announced by each peer.
+///
+/// The tracker is cheap to clone, all clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct PeerHeadTracker {
+    inner: Arc<Mutex<PeerHeads>>,
+}
+
+#[derive(Debug, Default)]
+struct PeerHeads {
+    /// Announced head and the sequence number of the last update, per peer.
+    heads: HashMap<PeerId, (u64, PeerHead)>,
+    /// Sequence number of the next update.
+    next_seq: u64,
+}
+
+impl PeerHeadTracker {
+    /// Records a block announced by the given peer.
+    ///
+    /// Announcements below the highest head already known for the peer are ignored. Once
+    /// [`MAX_TRACKED_PEERS`] are tracked, the peer that announced least recently is dropped.
+    pub fn on_new_block(&self, peer_id: PeerId, head: PeerHead) {
+        let mut inner = self.inner.lock();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+
+        if let Some((last_seq, known)) = inner.heads.get_mut(&peer_id) {
+            if head.number >= known.number {
+                *last_seq = seq;
+                *known = head;
+            }
+            return;
+        }
+
+        if inner.heads.len() >= MAX_TRACKED_PEERS {
+            let oldest = inner.heads.iter().min_by_key(|(_, (seq, _))| *seq).map(|(peer, _)| *peer);
+            if let Some(oldest) = oldest {
+                inner.heads.remove(&oldest);
+            }
+        }
+        inner.heads.insert(peer_id, (seq, head));
+    }
+
+    /// Stops tracking the given peer.
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        self.inner.lock().heads.remove(peer_id);
+    }
+
+    /// Returns the number of tracked peers.
+    pub fn len(&self) -> usize {
+        self.inner.lock().heads.len()
+    }
+
+    /// Returns `true` if no peer head is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the head to sync towards.
+    ///
+    /// Heads more than [`MAX_HEAD_DISTANCE_FROM_MEDIAN`] blocks above the (lower) median of all
+    /// announced heads are ignored, so a single peer lying about its head can't pull the target
+    /// away from the honest majority. Of the remaining heads the highest block wins. Like the
+    /// fork choice, ties prefer the block sealed in turn and then the lower hash, the total
+    /// difficulty a peer announces isn't covered by the seal.
+    pub fn best_target(&self) -> Option<PeerHead> {
+        let inner = self.inner.lock();
+        let mut numbers: Vec<u64> = inner.heads.values().map(|(_, head)| head.number).collect();
+        if numbers.is_empty() {
+            return None;
+        }
+        numbers.sort_unstable();
+        let median = numbers[(numbers.len() - 1) / 2];
+        let max_number = median.saturating_add(MAX_HEAD_DISTANCE_FROM_MEDIAN);
+
+        inner
+            .heads
+            .values()
+            .map(|(_, head)| *head)
+            .filter(|head| head.number <= max_number)
+            .max_by_key(|head| (head.number, head.difficulty, Reverse(head.hash)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(number: u64) -> PeerHead {
+        PeerHead { number, hash: B256::with_last_byte(number as u8), difficulty: U256::from(2) }
+    }
+
+    #[test]
+    fn test_best_target_ignores_outliers() {
+        let tracker = PeerHeadTracker::default();
+        assert_eq!(tracker.best_target(), None);
+
+        for number in 1_000..1_005 {
+            tracker.on_new_block(PeerId::random(), head(number));
+        }
+        let liar = PeerId::random();
+        tracker.on_new_block(liar, head(1_000_000_000));
+        assert_eq!(tracker.len(), 6);
+        assert_eq!(tracker.best_target(), Some(head(1_004)));
+
+        tracker.remove_peer(&liar);
+        assert_eq!(tracker.best_target(), Some(head(1_004)));
+    }
+
+    #[test]
+    fn test_best_target_tie_break_by_sealed_difficulty_and_hash() {
+        let tracker = PeerHeadTracker::default();
+        let out_of_turn =
+            PeerHead { difficulty: U256::from(1), hash: B256::repeat_byte(0x01), ..head(100) };
+        let in_turn = PeerHead { hash: B256::repeat_byte(0xff), ..head(100) };
+        tracker.on_new_block(PeerId::random(), out_of_turn);
+        tracker.on_new_block(PeerId::random(), in_turn);
+        assert_eq!(tracker.best_target(), Some(in_turn));
+
+        // among blocks of the same difficulty the lower hash wins
+        let lower = PeerHead { hash: B256::repeat_byte(0x02), ..head(100) };
+        tracker.on_new_block(PeerId::random(), lower);
+        assert_eq!(tracker.best_target(), Some(lower));
+    }
+
+    #[test]
+    fn test_on_new_block_keeps_highest_head() {
+        let tracker = PeerHeadTracker::default();
+        let peer = PeerId::random();
+        tracker.on_new_block(peer, head(10));
+        tracker.on_new_block(peer, head(9));
+        assert_eq!(tracker.best_target(), Some(head(10)));
+        tracker.on_new_block(peer, head(11));
+        assert_eq!(tracker.best_target(), Some(head(11)));
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_tracked_peers_bounded() {
+        let tracker = PeerHeadTracker::default();
+        let first = PeerId::random();
+        tracker.on_new_block(first, head(1));
+        for _ in 0..MAX_TRACKED_PEERS {
+            tracker.on_new_block(PeerId::random(), head(2));
+        }
+        assert_eq!(tracker.len(), MAX_TRACKED_PEERS);
+        assert!(!tracker.inner.lock().heads.contains_key(&first));
+    }
+}
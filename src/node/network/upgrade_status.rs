@@ -1,7 +1,7 @@
 //! Implement BSC upgrade message which is required during handshake with other BSC clients, e.g.,
 //! geth.
-use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable, EMPTY_LIST_CODE};
+use bytes::{BufMut, Bytes, BytesMut};
 
 /// The message id for the upgrade status message, used in the BSC handshake.
 const UPGRADE_STATUS_MESSAGE_ID: u8 = 0x0b;
@@ -28,7 +28,24 @@ impl Decodable for UpgradeStatus {
         if message_id != UPGRADE_STATUS_MESSAGE_ID {
             return Err(alloy_rlp::Error::Custom("Invalid message ID"));
         }
-        buf.advance(1);
+
+        // bsc-geth wraps the extension into the `UpgradeStatusPacket` list, while the extension
+        // may also directly follow the message id, accept both.
+        let mut payload = *buf;
+        let header = Header::decode(&mut payload)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        if payload.first().is_some_and(|b| *b >= EMPTY_LIST_CODE) {
+            if payload.len() < header.payload_length {
+                return Err(alloy_rlp::Error::InputTooShort);
+            }
+            let (mut packet, rest) = payload.split_at(header.payload_length);
+            let extension = UpgradeStatusExtension::decode(&mut packet)?;
+            *buf = rest;
+            return Ok(Self { extension })
+        }
+
         let extension = UpgradeStatusExtension::decode(buf)?;
         Ok(Self { extension })
     }
@@ -41,6 +58,11 @@ impl UpgradeStatus {
         self.encode(&mut out);
         out.freeze()
     }
+
+    /// Decode the upgrade status message from RLPx bytes, including the message id.
+    pub fn from_rlpx(mut buf: &[u8]) -> alloy_rlp::Result<Self> {
+        Self::decode(&mut buf)
+    }
 }
 
 /// The extension to define whether to enable or disable the flag.
@@ -52,3 +74,45 @@ pub struct UpgradeStatusExtension {
     /// To notify a peer to disable the broadcast of transactions or not.
     pub disable_peer_tx_broadcast: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_upgrade_status_roundtrip() {
+        for disable_peer_tx_broadcast in [false, true] {
+            let status =
+                UpgradeStatus { extension: UpgradeStatusExtension { disable_peer_tx_broadcast } };
+            let encoded = status.clone().into_rlpx();
+            assert_eq!(encoded.len(), 3);
+            assert_eq!(UpgradeStatus::from_rlpx(&encoded).unwrap(), status);
+        }
+        assert_eq!(
+            UpgradeStatus {
+                extension: UpgradeStatusExtension { disable_peer_tx_broadcast: false }
+            }
+            .into_rlpx()[..],
+            hex!("0bc180")
+        );
+    }
+
+    #[test]
+    fn test_decode_geth_upgrade_status() {
+        // `UpgradeStatusPacket` as sent by bsc-geth
+        let status = UpgradeStatus::from_rlpx(&hex!("0bc2c180")).unwrap();
+        assert!(!status.extension.disable_peer_tx_broadcast);
+
+        let status = UpgradeStatus::from_rlpx(&hex!("0bc2c101")).unwrap();
+        assert!(status.extension.disable_peer_tx_broadcast);
+    }
+
+    #[test]
+    fn test_decode_invalid_upgrade_status() {
+        assert!(UpgradeStatus::from_rlpx(&hex!("0ac180")).is_err());
+        assert!(UpgradeStatus::from_rlpx(&hex!("0b80")).is_err());
+        assert!(UpgradeStatus::from_rlpx(&hex!("0bc3c180")).is_err());
+        assert!(UpgradeStatus::from_rlpx(&[]).is_err());
+    }
+}
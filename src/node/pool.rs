@@ -0,0 +1,376 @@
+use crate::node::BscNode;
+use alloy_consensus::{BlockHeader, Transaction};
+use alloy_eips::{eip7840::BlobParams, merge::EPOCH_SLOTS};
+use reth::{
+    api::FullNodeTypes,
+    builder::{
+        components::{create_blob_store_with_cache, PoolBuilder, TxPoolBuilder},
+        BuilderContext,
+    },
+    transaction_pool::{
+        blobstore::DiskFileBlobStore,
+        error::{Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolTransactionError},
+        CoinbaseTipOrdering, EthPooledTransaction, EthTransactionValidator, Pool, PoolTransaction,
+        TransactionOrigin, TransactionValidationOutcome, TransactionValidationTaskExecutor,
+        TransactionValidator,
+    },
+};
+use reth_chainspec::EthChainSpec;
+use reth_primitives::SealedBlock;
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+use tracing::{debug, info};
+
+/// Type alias for the BSC transaction pool.
+pub type BscTransactionPool<Client, S> = Pool<
+    TransactionValidationTaskExecutor<
+        BscTransactionValidator<EthTransactionValidator<Client, EthPooledTransaction>>,
+    >,
+    CoinbaseTipOrdering<EthPooledTransaction>,
+    S,
+>;
+
+/// A basic BSC transaction pool builder.
+///
+/// Mirrors the ethereum pool builder, but wraps the validator with the BSC specific checks.
+#[derive(Debug, Default, Clone, Copy)]
+#[non_exhaustive]
+pub struct BscPoolBuilder;
+
+impl<Node> PoolBuilder<Node> for BscPoolBuilder
+where
+    Node: FullNodeTypes<Types = BscNode>,
+{
+    type Pool = BscTransactionPool<Node::Provider, DiskFileBlobStore>;
+
+    async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
+        let pool_config = ctx.pool_config();
+
+        let current_timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+        let blob_params = ctx
+            .chain_spec()
+            .blob_params_at_timestamp(current_timestamp)
+            .unwrap_or_else(BlobParams::cancun);
+        let blob_cache_size = pool_config
+            .blob_cache_size
+            .or(Some((blob_params.target_blob_count * EPOCH_SLOTS * 2) as u32));
+
+        let blob_store = create_blob_store_with_cache(ctx, blob_cache_size)?;
+        let max_tx_size = ctx.chain_spec().max_tx_size_bytes();
+        let eip155_block = ctx.chain_spec().eip155_block();
+
+        let validator = TransactionValidationTaskExecutor::eth_builder(ctx.provider().clone())
+            .with_head_timestamp(ctx.head().timestamp)
+            .with_max_tx_input_bytes(ctx.config().txpool.max_tx_input_bytes)
+            .kzg_settings(ctx.kzg_settings()?)
+            .with_local_transactions_config(pool_config.local_transactions_config.clone())
+            .set_tx_fee_cap(ctx.config().rpc.rpc_tx_fee_cap)
+            .with_additional_tasks(ctx.config().txpool.additional_validation_tasks)
+            .build_with_tasks(ctx.task_executor().clone(), blob_store.clone())
+            .map(|validator| {
+                BscTransactionValidator::new(validator, max_tx_size)
+                    .with_eip155_block(eip155_block)
+                    .with_head_number(ctx.head().number)
+                    .with_max_blob_count(blob_params.max_blob_count)
+            });
+
+        let transaction_pool = TxPoolBuilder::new(ctx)
+            .with_validator(validator)
+            .build_and_spawn_maintenance_task(blob_store, pool_config)?;
+
+        info!(target: "reth::cli", "Transaction pool initialized");
+        debug!(target: "reth::cli", "Spawned txpool maintenance task");
+
+        Ok(transaction_pool)
+    }
+}
+
+/// BSC specific reasons for rejecting a transaction from the pool.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BscPoolTransactionError {
+    /// The transaction doesn't commit to a chain id.
+    #[error("transaction is not replay protected (EIP-155)")]
+    NotReplayProtected,
+}
+
+impl PoolTransactionError for BscPoolTransactionError {
+    fn is_bad_transaction(&self) -> bool {
+        // valid before EIP-155, peers relaying such transactions aren't misbehaving
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Transaction validator enforcing the BSC specific mempool rules on top of the inner validator.
+#[derive(Debug, Clone)]
+pub struct BscTransactionValidator<V> {
+    /// The ethereum validator.
+    inner: V,
+    /// Maximum encoded size of a transaction, see
+    /// [`BscChainSpec::max_tx_size_bytes`](crate::chainspec::BscChainSpec::max_tx_size_bytes).
+    max_tx_size: usize,
+    /// Block from which transactions without replay protection are rejected.
+    eip155_block: u64,
+    /// Number of the current head block.
+    head_number: Arc<AtomicU64>,
+    /// Maximum number of blobs per transaction, BSC keeps the Cancun limit after Prague.
+    max_blob_count: u64,
+}
+
+impl<V> BscTransactionValidator<V> {
+    /// Create a new instance of [`BscTransactionValidator`]
+    pub fn new(inner: V, max_tx_size: usize) -> Self {
+        Self {
+            inner,
+            max_tx_size,
+            eip155_block: 0,
+            head_number: Default::default(),
+            max_blob_count: BlobParams::cancun().max_blob_count,
+        }
+    }
+
+    /// Sets the block from which transactions without replay protection are rejected.
+    pub const fn with_eip155_block(mut self, eip155_block: u64) -> Self {
+        self.eip155_block = eip155_block;
+        self
+    }
+
+    /// Sets the number of the current head block.
+    pub fn with_head_number(self, head_number: u64) -> Self {
+        self.head_number.store(head_number, Ordering::Relaxed);
+        self
+    }
+
+    /// Sets the maximum number of blobs a transaction may carry.
+    pub const fn with_max_blob_count(mut self, max_blob_count: u64) -> Self {
+        self.max_blob_count = max_blob_count;
+        self
+    }
+
+    /// Returns the inner validator.
+    pub const fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    /// Ensures the encoded transaction doesn't exceed the BSC mempool size limit.
+    fn ensure_max_tx_size<T: PoolTransaction>(
+        &self,
+        transaction: &T,
+    ) -> Result<(), InvalidPoolTransactionError> {
+        let size = transaction.encoded_length();
+        if size > self.max_tx_size {
+            return Err(InvalidPoolTransactionError::OversizedData(size, self.max_tx_size));
+        }
+        Ok(())
+    }
+
+    /// Ensures the transaction carries a chain id once EIP-155 is enforced for the next block.
+    fn ensure_replay_protected<T: PoolTransaction>(
+        &self,
+        transaction: &T,
+    ) -> Result<(), InvalidPoolTransactionError> {
+        let next_block = self.head_number.load(Ordering::Relaxed) + 1;
+        if transaction.chain_id().is_none() && next_block >= self.eip155_block {
+            return Err(InvalidPoolTransactionError::other(
+                BscPoolTransactionError::NotReplayProtected,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensures a blob transaction doesn't carry more blobs than a BSC block allows.
+    fn ensure_blob_count<T: PoolTransaction>(
+        &self,
+        transaction: &T,
+    ) -> Result<(), InvalidPoolTransactionError> {
+        let blob_count = transaction.blob_count().unwrap_or_default();
+        if blob_count > self.max_blob_count {
+            return Err(InvalidPoolTransactionError::Eip4844(
+                Eip4844PoolTransactionError::TooManyEip4844Blobs {
+                    have: blob_count,
+                    permitted: self.max_blob_count,
+                },
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<V> TransactionValidator for BscTransactionValidator<V>
+where
+    V: TransactionValidator,
+{
+    type Transaction = V::Transaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        if let Err(err) = self
+            .ensure_max_tx_size(&transaction)
+            .and_then(|_| self.ensure_replay_protected(&transaction))
+            .and_then(|_| self.ensure_blob_count(&transaction))
+        {
+            return TransactionValidationOutcome::Invalid(transaction, err);
+        }
+
+        self.inner.validate_transaction(origin, transaction).await
+    }
+
+    fn on_new_head_block<B>(&self, new_tip_block: &SealedBlock<B>)
+    where
+        B: reth_primitives_traits::Block,
+    {
+        self.head_number.store(new_tip_block.header().number(), Ordering::Relaxed);
+        self.inner.on_new_head_block(new_tip_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chainspec::{bsc::bsc_mainnet, BscChainSpec};
+    use alloy_consensus::{TxEip4844, TxLegacy};
+    use alloy_eips::{eip2718::Encodable2718, eip4844::fake_exponential};
+    use alloy_primitives::{Address, Bytes, Signature, TxKind, B256, U256};
+    use reth_primitives::{Recovered, Transaction, TransactionSigned};
+
+    fn pooled_tx(input_len: usize) -> EthPooledTransaction {
+        legacy_pooled_tx(Some(56), input_len)
+    }
+
+    fn legacy_pooled_tx(chain_id: Option<u64>, input_len: usize) -> EthPooledTransaction {
+        let tx = TransactionSigned::new_unhashed(
+            Transaction::Legacy(TxLegacy {
+                chain_id,
+                nonce: 0,
+                gas_limit: 30_000_000,
+                gas_price: 1_000_000_000,
+                to: TxKind::Call(Address::ZERO),
+                value: U256::ZERO,
+                input: Bytes::from(vec![0xab; input_len]),
+            }),
+            Signature::new(U256::from(1), U256::from(1), false),
+        );
+        let encoded_length = tx.encode_2718_len();
+        EthPooledTransaction::new(Recovered::new_unchecked(tx, Address::ZERO), encoded_length)
+    }
+
+    fn blob_pooled_tx(blob_count: usize) -> EthPooledTransaction {
+        let tx = TransactionSigned::new_unhashed(
+            Transaction::Eip4844(TxEip4844 {
+                chain_id: 56,
+                gas_limit: 21_000,
+                max_fee_per_gas: 1_000_000_000,
+                max_fee_per_blob_gas: 1,
+                blob_versioned_hashes: vec![B256::ZERO; blob_count],
+                ..Default::default()
+            }),
+            Signature::new(U256::from(1), U256::from(1), false),
+        );
+        let encoded_length = tx.encode_2718_len();
+        EthPooledTransaction::new(Recovered::new_unchecked(tx, Address::ZERO), encoded_length)
+    }
+
+    /// Returns a transaction whose encoded length is exactly `size` bytes.
+    fn pooled_tx_with_size(size: usize) -> EthPooledTransaction {
+        let overhead = pooled_tx(size).encoded_length() - size;
+        let tx = pooled_tx(size - overhead);
+        assert_eq!(tx.encoded_length(), size);
+        tx
+    }
+
+    #[test]
+    fn test_max_tx_size() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        let limit = chain_spec.max_tx_size_bytes();
+        assert_eq!(limit, 128 * 1024);
+
+        let validator = BscTransactionValidator::new((), limit);
+
+        let below = pooled_tx_with_size(limit - 1);
+        assert!(validator.ensure_max_tx_size(&below).is_ok());
+
+        let at_limit = pooled_tx_with_size(limit);
+        assert!(validator.ensure_max_tx_size(&at_limit).is_ok());
+
+        let above = pooled_tx_with_size(limit + 1);
+        assert!(matches!(
+            validator.ensure_max_tx_size(&above),
+            Err(InvalidPoolTransactionError::OversizedData(size, max))
+                if size == limit + 1 && max == limit
+        ));
+    }
+
+    #[test]
+    fn test_unprotected_tx() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        assert_eq!(chain_spec.eip155_block(), 0);
+
+        let limit = chain_spec.max_tx_size_bytes();
+        let protected = legacy_pooled_tx(Some(56), 0);
+        let unprotected = legacy_pooled_tx(None, 0);
+
+        let validator = BscTransactionValidator::new((), limit)
+            .with_eip155_block(chain_spec.eip155_block())
+            .with_head_number(1_000);
+        assert!(validator.ensure_replay_protected(&protected).is_ok());
+        let Err(InvalidPoolTransactionError::Other(err)) =
+            validator.ensure_replay_protected(&unprotected)
+        else {
+            panic!("expected the transaction to be rejected");
+        };
+        assert_eq!(err.as_any().downcast_ref(), Some(&BscPoolTransactionError::NotReplayProtected));
+        assert_eq!(err.to_string(), "transaction is not replay protected (EIP-155)");
+        assert!(!err.is_bad_transaction());
+
+        // accepted while the enforcement height is above the next block
+        let validator = BscTransactionValidator::new((), limit)
+            .with_eip155_block(1_002)
+            .with_head_number(1_000);
+        assert!(validator.ensure_replay_protected(&unprotected).is_ok());
+        validator.head_number.store(1_001, Ordering::Relaxed);
+        assert!(validator.ensure_replay_protected(&unprotected).is_err());
+    }
+
+    #[test]
+    fn test_blob_count() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        let blob_params = chain_spec.blob_params_at_timestamp(u64::MAX).unwrap();
+        assert_eq!(blob_params.max_blob_count, 6);
+
+        let validator = BscTransactionValidator::new((), chain_spec.max_tx_size_bytes())
+            .with_max_blob_count(blob_params.max_blob_count);
+        assert!(validator.ensure_blob_count(&legacy_pooled_tx(Some(56), 0)).is_ok());
+        assert!(validator.ensure_blob_count(&blob_pooled_tx(6)).is_ok());
+        assert!(matches!(
+            validator.ensure_blob_count(&blob_pooled_tx(7)),
+            Err(InvalidPoolTransactionError::Eip4844(
+                Eip4844PoolTransactionError::TooManyEip4844Blobs { have: 7, permitted: 6 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_blob_fee() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        let blob_params = chain_spec.blob_params_at_timestamp(u64::MAX).unwrap();
+
+        // min blob gas price of 1 wei and the Cancun update fraction
+        let excess_blob_gas = 10_000_000;
+        let expected = fake_exponential(1, excess_blob_gas as u128, 3_338_477);
+        assert_eq!(expected, 19);
+        assert_eq!(blob_params.calc_blob_fee(excess_blob_gas), expected);
+        assert_eq!(blob_params.calc_blob_fee(0), 1);
+    }
+}
@@ -0,0 +1,948 @@
+//! BSC specific RPC endpoints.
+//!
+//! The `bsc` and `parlia` namespaces are served on every enabled transport, subscriptions need
+//! a websocket or IPC connection. The `debug` and the administrative `bsc` endpoints are only
+//! served where the `debug` respectively `admin` namespace is enabled, e.g. via `--http.api`.
+
+use crate::{
+    chainspec::BscChainSpec,
+    consensus::{
+        block_interval_ms, epoch_length_at, rotation_at, ParliaConsensusErr, ProposerArchive,
+        ProposerRecord, Rotation, ValidatorPerfTracker, ValidatorPerformance, VoteAddress,
+    },
+    hardforks::{bsc::BscHardfork, BscHardforks},
+    node::{
+        evm::{
+            config::revm_spec_by_timestamp_and_block_number,
+            listener::{SlashEvent, SlashingListener},
+        },
+        health::{HealthInputs, HealthReport, HealthThresholds},
+        network::{
+            block_import::service::{HeadOverride, ImportStatus, RpcBlockImport},
+            peer_heads::PeerHeadTracker,
+            BscNewBlock,
+        },
+    },
+    system_contracts::{
+        applied_system_contract_upgrades, pending_system_contract_upgrades, SystemContractError,
+        SystemContractUpgrade,
+    },
+};
+use alloy_consensus::Header;
+use alloy_eips::BlockNumberOrTag;
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_rlp::Decodable;
+use jsonrpsee::{
+    core::{async_trait, RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    PendingSubscriptionSink, SubscriptionMessage,
+};
+use jsonrpsee_types::{
+    error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+    ErrorObject, ErrorObjectOwned,
+};
+use reth_chainspec::{ChainSpecProvider, EthChainSpec};
+use reth_ethereum_forks::Hardfork;
+use reth_provider::{BlockNumReader, BlockReaderIdExt, HeaderProvider};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+
+/// System contract code deployed by a hardfork.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardforkUpgrades {
+    /// Name of the hardfork.
+    pub hardfork: String,
+    /// New code of the upgraded contracts, ordered by address.
+    pub contracts: Vec<SystemContractUpgrade>,
+}
+
+impl From<(BscHardfork, Vec<SystemContractUpgrade>)> for HardforkUpgrades {
+    fn from((hardfork, contracts): (BscHardfork, Vec<SystemContractUpgrade>)) -> Self {
+        Self { hardfork: hardfork.name().to_string(), contracts }
+    }
+}
+
+/// Proposal counters of a validator over a window of recent blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPerformance {
+    /// The counters of the window.
+    #[serde(flatten)]
+    pub counters: ValidatorPerformance,
+    /// Share of its in-turn slots the validator sealed, `null` if it had none.
+    pub uptime: Option<f64>,
+}
+
+impl From<ValidatorPerformance> for WindowPerformance {
+    fn from(counters: ValidatorPerformance) -> Self {
+        Self { counters, uptime: counters.uptime() }
+    }
+}
+
+/// BSC chain endpoints.
+#[rpc(server, namespace = "bsc")]
+pub trait BscApi {
+    /// Returns the system contract upgrades of the hardforks not active at the head yet, in
+    /// activation order.
+    #[method(name = "upcomingUpgrades")]
+    fn upcoming_upgrades(&self) -> RpcResult<Vec<HardforkUpgrades>>;
+
+    /// Returns the system contract upgrades applied at the given block, empty if no hardfork
+    /// activated at it.
+    #[method(name = "upgradeHistory")]
+    fn upgrade_history(&self, block: BlockNumberOrTag) -> RpcResult<Vec<HardforkUpgrades>>;
+
+    /// Returns the in-turn, out-of-turn and missed slots of the validator over the recent
+    /// canonical blocks, smallest window first.
+    #[method(name = "validatorPerformance")]
+    fn validator_performance(&self, validator: Address) -> RpcResult<Vec<WindowPerformance>>;
+
+    /// Checks the BSC specific sync invariants of the node. The overall status comes with the
+    /// HTTP status code a load balancer expects.
+    #[method(name = "health")]
+    fn health(&self) -> RpcResult<HealthReport>;
+}
+
+/// Implementation of the [`BscApiServer`].
+#[derive(Debug, Clone)]
+pub struct BscRpc<Provider> {
+    provider: Provider,
+    performance: ValidatorPerfTracker,
+    peer_heads: PeerHeadTracker,
+    health_thresholds: HealthThresholds,
+}
+
+impl<Provider> BscRpc<Provider> {
+    /// Creates the BSC endpoints reading the chain from the given provider, the validator
+    /// performance from the given tracker and the peers from the heads they announced.
+    pub fn new(
+        provider: Provider,
+        performance: ValidatorPerfTracker,
+        peer_heads: PeerHeadTracker,
+    ) -> Self {
+        Self { provider, performance, peer_heads, health_thresholds: HealthThresholds::default() }
+    }
+}
+
+impl<Provider> BscApiServer for BscRpc<Provider>
+where
+    Provider: BlockReaderIdExt<Header = Header>
+        + ChainSpecProvider<ChainSpec = BscChainSpec>
+        + Clone
+        + 'static,
+{
+    fn upcoming_upgrades(&self) -> RpcResult<Vec<HardforkUpgrades>> {
+        let head = self
+            .provider
+            .latest_header()
+            .map_err(internal_error)?
+            .ok_or_else(|| internal_error("no head block"))?;
+        upcoming_upgrades(&self.provider.chain_spec(), &head)
+    }
+
+    fn upgrade_history(&self, block: BlockNumberOrTag) -> RpcResult<Vec<HardforkUpgrades>> {
+        let header = self
+            .provider
+            .header_by_number_or_tag(block)
+            .map_err(internal_error)?
+            .ok_or_else(|| invalid_params(format!("unknown block {block}")))?;
+        // the genesis block has no parent to transition from
+        let Some(parent) = self.provider.header(&header.parent_hash).map_err(internal_error)?
+        else {
+            return Ok(Vec::new())
+        };
+        upgrade_history(&self.provider.chain_spec(), &header, parent.timestamp)
+    }
+
+    fn validator_performance(&self, validator: Address) -> RpcResult<Vec<WindowPerformance>> {
+        Ok(self.performance.performance(validator).into_iter().map(Into::into).collect())
+    }
+
+    fn health(&self) -> RpcResult<HealthReport> {
+        let inputs =
+            HealthInputs::collect(&self.provider, &self.peer_heads, &self.health_thresholds)
+                .map_err(internal_error)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok(inputs.evaluate(&self.health_thresholds, now.as_millis() as u64))
+    }
+}
+
+/// Returns the system contract upgrades of the hardforks not active at the given head.
+fn upcoming_upgrades(chain_spec: &BscChainSpec, head: &Header) -> RpcResult<Vec<HardforkUpgrades>> {
+    let pending = pending_system_contract_upgrades(chain_spec, head.number, head.timestamp)
+        .map_err(upgrades_error)?;
+    Ok(pending.into_iter().map(Into::into).collect())
+}
+
+/// Returns the system contract upgrades applied at the given block.
+fn upgrade_history(
+    chain_spec: &BscChainSpec,
+    header: &Header,
+    parent_timestamp: u64,
+) -> RpcResult<Vec<HardforkUpgrades>> {
+    let applied = applied_system_contract_upgrades(
+        chain_spec,
+        header.number,
+        header.timestamp,
+        parent_timestamp,
+    )
+    .map_err(upgrades_error)?;
+    Ok(applied.into_iter().map(Into::into).collect())
+}
+
+/// Sync progress of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BscSyncStatus {
+    /// Whether peers announced valid blocks above the canonical head.
+    pub is_syncing: bool,
+    /// Number of the canonical head.
+    pub current_block: u64,
+    /// Number of the best head announced by peers, the canonical head if higher.
+    pub highest_block: u64,
+}
+
+/// Chain and consensus state of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BscNodeInfo {
+    /// Chain id of the network.
+    pub chain_id: u64,
+    /// Latest hardfork active at the canonical head.
+    pub hardfork: String,
+    /// Number of validators sealing the canonical head, `null` if their epoch header predates
+    /// Luban.
+    pub validator_count: Option<u64>,
+}
+
+/// Administrative BSC endpoints.
+#[rpc(server, namespace = "bsc")]
+pub trait BscAdminApi {
+    /// Pins the fork choice to the given block like `--debug.tip`, or releases the pin if
+    /// `null`.
+    #[method(name = "setHeadOverride")]
+    fn set_head_override(&self, hash: Option<B256>) -> RpcResult<()>;
+
+    /// Returns the canonical head and the best head announced by peers.
+    #[method(name = "syncStatus")]
+    fn sync_status(&self) -> RpcResult<BscSyncStatus>;
+
+    /// Returns the chain id, the active hardfork and the number of validators at the canonical
+    /// head.
+    #[method(name = "nodeInfo")]
+    fn node_info(&self) -> RpcResult<BscNodeInfo>;
+}
+
+/// Implementation of the [`BscAdminApiServer`].
+#[derive(Debug, Clone)]
+pub struct BscAdminRpc<Provider> {
+    provider: Provider,
+    head_override: HeadOverride,
+    peer_heads: PeerHeadTracker,
+}
+
+impl<Provider> BscAdminRpc<Provider> {
+    /// Creates the administrative endpoints reading the chain from the given provider, moving
+    /// the given override and reporting the sync target from the heads announced by peers.
+    pub const fn new(
+        provider: Provider,
+        head_override: HeadOverride,
+        peer_heads: PeerHeadTracker,
+    ) -> Self {
+        Self { provider, head_override, peer_heads }
+    }
+}
+
+impl<Provider> BscAdminApiServer for BscAdminRpc<Provider>
+where
+    Provider: BlockReaderIdExt<Header = Header>
+        + ChainSpecProvider<ChainSpec = BscChainSpec>
+        + Clone
+        + 'static,
+{
+    fn set_head_override(&self, hash: Option<B256>) -> RpcResult<()> {
+        self.head_override.set(hash);
+        Ok(())
+    }
+
+    fn sync_status(&self) -> RpcResult<BscSyncStatus> {
+        let current_block = self.provider.best_block_number().map_err(internal_error)?;
+        let highest_block = self
+            .peer_heads
+            .best_target()
+            .map_or(current_block, |target| target.number.max(current_block));
+        Ok(BscSyncStatus {
+            is_syncing: highest_block > current_block,
+            current_block,
+            highest_block,
+        })
+    }
+
+    fn node_info(&self) -> RpcResult<BscNodeInfo> {
+        let chain_spec = self.provider.chain_spec();
+        let number = self.provider.best_block_number().map_err(internal_error)?;
+        let head = self
+            .provider
+            .header_by_number(number)
+            .map_err(internal_error)?
+            .ok_or_else(|| internal_error("no head block"))?;
+        let hardfork =
+            revm_spec_by_timestamp_and_block_number(chain_spec.clone(), head.timestamp, number);
+        let validator_count = match rotation_at(&*chain_spec, &self.provider, number) {
+            Ok(rotation) => Some(rotation.validators().len() as u64),
+            Err(ParliaConsensusErr::PreLubanValidators(_)) => None,
+            Err(err) => return Err(internal_error(err)),
+        };
+        Ok(BscNodeInfo {
+            chain_id: chain_spec.chain().id(),
+            hardfork: hardfork.name().to_string(),
+            validator_count,
+        })
+    }
+}
+
+/// Consensus parameters in effect at a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusParams {
+    /// Number of blocks between two epoch headers.
+    pub epoch_length: u64,
+    /// Consecutive blocks sealed by each validator.
+    pub turn_length: u64,
+    /// Interval between two blocks.
+    pub block_interval_ms: u64,
+    /// Whether epoch headers carry the vote addresses of the validators.
+    pub is_luban: bool,
+    /// Whether epoch headers carry the turn length.
+    pub is_bohr: bool,
+    /// Whether Lorentz shortened the block interval and lengthened the epochs.
+    pub is_lorentz: bool,
+    /// Whether Maxwell shortened the block interval and lengthened the epochs.
+    pub is_maxwell: bool,
+}
+
+/// Parlia consensus endpoints.
+#[rpc(server, namespace = "parlia")]
+pub trait ParliaApi {
+    /// Subscribes to the validators slashed in canonical blocks.
+    #[subscription(
+        name = "subscribeSlashing",
+        unsubscribe = "unsubscribeSlashing",
+        item = SlashEvent
+    )]
+    async fn subscribe_slashing(&self) -> SubscriptionResult;
+
+    /// Returns the validator that sealed the given canonical block and whether it was in turn,
+    /// `null` if the block is not kept in the proposer archive.
+    #[method(name = "getProposer")]
+    fn get_proposer(&self, block: BlockNumberOrTag) -> RpcResult<Option<ProposerRecord>>;
+
+    /// Returns the BLS key the given validator votes with, as announced by the epoch header
+    /// whose validators seal the given canonical block.
+    #[method(name = "getVoteAddress")]
+    fn get_vote_address(
+        &self,
+        block: BlockNumberOrTag,
+        validator: Address,
+    ) -> RpcResult<VoteAddress>;
+
+    /// Returns the epoch length, the turn length and the block interval in effect at the given
+    /// canonical block, along with the hardforks changing them.
+    #[method(name = "getConsensusParams")]
+    fn get_consensus_params(&self, block: BlockNumberOrTag) -> RpcResult<ConsensusParams>;
+}
+
+/// Implementation of the [`ParliaApiServer`].
+#[derive(Debug, Clone)]
+pub struct ParliaRpc<Provider> {
+    provider: Provider,
+    slashing: SlashingListener,
+    proposers: ProposerArchive,
+}
+
+impl<Provider> ParliaRpc<Provider> {
+    /// Creates the Parlia endpoints publishing the slashes of the given listener and answering
+    /// proposer lookups from the given archive.
+    pub const fn new(
+        provider: Provider,
+        slashing: SlashingListener,
+        proposers: ProposerArchive,
+    ) -> Self {
+        Self { provider, slashing, proposers }
+    }
+}
+
+impl<Provider> ParliaRpc<Provider>
+where
+    Provider: BlockReaderIdExt<Header = Header> + ChainSpecProvider<ChainSpec = BscChainSpec>,
+{
+    /// Returns the header of the given canonical block and the validator rotation sealing it.
+    fn rotation(&self, block: BlockNumberOrTag) -> RpcResult<(Header, Rotation)> {
+        let header = self
+            .provider
+            .header_by_number_or_tag(block)
+            .map_err(internal_error)?
+            .ok_or_else(|| invalid_params(format!("unknown block {block}")))?;
+        let rotation = rotation_at(&*self.provider.chain_spec(), &self.provider, header.number)
+            .map_err(rotation_error)?;
+        Ok((header, rotation))
+    }
+}
+
+#[async_trait]
+impl<Provider> ParliaApiServer for ParliaRpc<Provider>
+where
+    Provider: BlockReaderIdExt<Header = Header>
+        + ChainSpecProvider<ChainSpec = BscChainSpec>
+        + Clone
+        + 'static,
+{
+    async fn subscribe_slashing(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        // subscribe before confirming, so no slash published after the confirmation is missed
+        let mut events = self.slashing.subscribe();
+        let sink = pending.accept().await?;
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = sink.closed() => break,
+                    event = events.recv() => event,
+                };
+                let event = match event {
+                    Ok(event) => event,
+                    // a subscriber falling behind misses the oldest slashes
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(message) =
+                    SubscriptionMessage::new(sink.method_name(), sink.subscription_id(), &event)
+                else {
+                    break
+                };
+                if sink.send(message).await.is_err() {
+                    break
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn get_proposer(&self, block: BlockNumberOrTag) -> RpcResult<Option<ProposerRecord>> {
+        let header = self
+            .provider
+            .sealed_header_by_number_or_tag(block)
+            .map_err(internal_error)?
+            .ok_or_else(|| invalid_params(format!("unknown block {block}")))?;
+        // the archive may not have caught up with a reorg the provider already reflects
+        Ok(self.proposers.get(header.number).filter(|record| record.hash == header.hash()))
+    }
+
+    fn get_vote_address(
+        &self,
+        block: BlockNumberOrTag,
+        validator: Address,
+    ) -> RpcResult<VoteAddress> {
+        let (header, rotation) = self.rotation(block)?;
+        rotation.vote_address(validator).ok_or_else(|| {
+            invalid_params(format!("{validator} is no validator at block {}", header.number))
+        })
+    }
+
+    fn get_consensus_params(&self, block: BlockNumberOrTag) -> RpcResult<ConsensusParams> {
+        let chain_spec = self.provider.chain_spec();
+        let header = self
+            .provider
+            .header_by_number_or_tag(block)
+            .map_err(internal_error)?
+            .ok_or_else(|| invalid_params(format!("unknown block {block}")))?;
+        let epoch_length =
+            epoch_length_at(&*chain_spec, &self.provider, header.number).map_err(internal_error)?;
+        let turn_length = match rotation_at(&*chain_spec, &self.provider, header.number) {
+            Ok(rotation) => rotation.turn_length(),
+            // each validator seals a single block until Bohr
+            Err(ParliaConsensusErr::PreLubanValidators(_)) => 1,
+            Err(err) => return Err(internal_error(err)),
+        };
+
+        Ok(ConsensusParams {
+            epoch_length,
+            turn_length,
+            block_interval_ms: block_interval_ms(&*chain_spec, header.timestamp),
+            is_luban: chain_spec.is_luban_active_at_block(header.number),
+            is_bohr: chain_spec.is_bohr_active_at_timestamp(header.timestamp),
+            is_lorentz: chain_spec.is_lorentz_active_at_timestamp(header.timestamp),
+            is_maxwell: chain_spec.is_maxwell_active_at_timestamp(header.timestamp),
+        })
+    }
+}
+
+/// Debug endpoints, e.g. for feeding blocks to a node without p2p network.
+#[rpc(server, namespace = "debug")]
+pub trait BscDebugApi {
+    /// Imports an RLP encoded `NewBlock` message as if a peer announced it, and returns the
+    /// import status once the engine processed the block.
+    #[method(name = "importBlock")]
+    async fn import_block(&self, rlp: Bytes) -> RpcResult<ImportStatus>;
+}
+
+/// Implementation of the [`BscDebugApiServer`].
+#[derive(Debug, Clone)]
+pub struct BscDebugRpc {
+    block_import: RpcBlockImport,
+}
+
+impl BscDebugRpc {
+    /// Creates the debug endpoints importing blocks via the given handle.
+    pub const fn new(block_import: RpcBlockImport) -> Self {
+        Self { block_import }
+    }
+}
+
+#[async_trait]
+impl BscDebugApiServer for BscDebugRpc {
+    async fn import_block(&self, rlp: Bytes) -> RpcResult<ImportStatus> {
+        let block = BscNewBlock::decode(&mut rlp.as_ref()).map_err(invalid_params)?;
+        self.block_import.import_block(block).await.map_err(internal_error)
+    }
+}
+
+fn invalid_params(err: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(INVALID_PARAMS_CODE, err.to_string(), None::<()>)
+}
+
+fn internal_error(err: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+}
+
+/// Blocks sealed by validators announced before Luban are rejected as invalid parameters, their
+/// rotation is not known.
+fn rotation_error(err: ParliaConsensusErr) -> ErrorObjectOwned {
+    match err {
+        ParliaConsensusErr::PreLubanValidators(_) => invalid_params(err),
+        err => internal_error(err),
+    }
+}
+
+fn upgrades_error(err: SystemContractError) -> ErrorObjectOwned {
+    internal_error(format!("failed to load system contract upgrades: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chainspec::bsc::bsc_mainnet,
+        consensus::{epoch_header, ParliaConsensus, ValidatorInfo, DIFF_INTURN},
+        node::{
+            engine_api::payload::BscPayloadTypes,
+            evm::listener::BscExecutionListener,
+            network::{block_import::service::ImportService, peer_heads::PeerHead},
+            primitives::BscPrimitives,
+        },
+        system_contracts::{system_contract_upgrades, STAKE_HUB_CONTRACT},
+        BscBlock,
+    };
+    use alloy_eips::BlockNumHash;
+    use alloy_primitives::{Address, U128};
+    use alloy_rlp::Encodable;
+    use alloy_rpc_types::engine::{PayloadStatus, PayloadStatusEnum};
+    use jsonrpsee::core::EmptyServerParams;
+    use jsonrpsee_core::server::MethodsError;
+    use reth_chainspec::ForkCondition;
+    use reth_engine_primitives::{
+        BeaconConsensusEngineHandle, BeaconEngineMessage, OnForkChoiceUpdated,
+    };
+    use reth_eth_wire::NewBlock;
+    use reth_ethereum_primitives::EthPrimitives;
+    use reth_network_api::PeerId;
+    use reth_provider::{noop::NoopProvider, test_utils::MockEthProvider};
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    /// Returns a provider serving the added headers as canonical blocks of BSC mainnet.
+    fn mainnet_provider() -> MockEthProvider<EthPrimitives, BscChainSpec> {
+        MockEthProvider::<EthPrimitives>::default()
+            .with_chain_spec(BscChainSpec::from(bsc_mainnet()))
+    }
+
+    /// Encodes a `NewBlock` message of an empty block at the given height.
+    fn new_block(number: u64) -> Bytes {
+        let mut block = BscBlock::default();
+        block.header.number = number;
+        let mut rlp = Vec::new();
+        BscNewBlock(NewBlock { block, td: U128::from(number) }).encode(&mut rlp);
+        rlp.into()
+    }
+
+    #[tokio::test]
+    async fn test_debug_import_block() {
+        // the engine rejects every block but the first
+        let (to_engine, mut from_engine) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = from_engine.recv().await {
+                match message {
+                    BeaconEngineMessage::NewPayload { payload, tx } => {
+                        let status = if payload.0.header.number == 1 {
+                            PayloadStatusEnum::Valid
+                        } else {
+                            PayloadStatusEnum::Invalid { validation_error: "bad block".into() }
+                        };
+                        tx.send(Ok(PayloadStatus::new(status, None))).unwrap();
+                    }
+                    BeaconEngineMessage::ForkchoiceUpdated { tx, .. } => {
+                        let status = PayloadStatus::new(PayloadStatusEnum::Valid, None);
+                        tx.send(Ok(OnForkChoiceUpdated::valid(status))).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let provider = NoopProvider::<_, BscPrimitives>::new(chain_spec);
+        let block_import = RpcBlockImport::default();
+        let (_to_import, from_network) = mpsc::unbounded_channel();
+        let (to_network, _import_outcome) = mpsc::unbounded_channel();
+        let service = ImportService::new(
+            Arc::new(ParliaConsensus::new(provider)),
+            BeaconConsensusEngineHandle::<BscPayloadTypes>::new(to_engine),
+            from_network,
+            to_network,
+        )
+        .with_rpc_block_import(&block_import);
+        tokio::spawn(async move { service.await.unwrap() });
+
+        let rpc = BscDebugRpc::new(block_import).into_rpc();
+        let status: ImportStatus = rpc.call("debug_importBlock", [new_block(1)]).await.unwrap();
+        assert_eq!(status, ImportStatus::Valid);
+        let status: ImportStatus = rpc.call("debug_importBlock", [new_block(2)]).await.unwrap();
+        assert!(matches!(status, ImportStatus::Invalid { error } if error.contains("bad block")));
+
+        // garbage is rejected before reaching the import
+        let err = rpc
+            .call::<_, ImportStatus>("debug_importBlock", [Bytes::from_static(&[0xc0])])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MethodsError::JsonRpc(err) if err.code() == INVALID_PARAMS_CODE));
+    }
+
+    #[tokio::test]
+    async fn test_validator_performance() {
+        let tracker = ValidatorPerfTracker::new(&[4, 8]);
+        let chain_spec = Arc::new(BscChainSpec::from(bsc_mainnet()));
+        let provider = NoopProvider::<_, BscPrimitives>::new(chain_spec);
+        let rpc = BscRpc::new(provider, tracker.clone(), PeerHeadTracker::default()).into_rpc();
+        let (validator, backup) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        for number in 0..8 {
+            let proposer = if number == 6 { backup } else { validator };
+            tracker.on_block(number, proposer, Some(validator));
+        }
+
+        let performance: Vec<WindowPerformance> =
+            rpc.call("bsc_validatorPerformance", [validator]).await.unwrap();
+        assert_eq!(
+            performance,
+            vec![
+                WindowPerformance {
+                    counters: ValidatorPerformance {
+                        window: 4,
+                        in_turn: 3,
+                        out_of_turn: 0,
+                        missed: 1
+                    },
+                    uptime: Some(0.75),
+                },
+                WindowPerformance {
+                    counters: ValidatorPerformance {
+                        window: 8,
+                        in_turn: 7,
+                        out_of_turn: 0,
+                        missed: 1
+                    },
+                    uptime: Some(0.875),
+                },
+            ]
+        );
+
+        let unknown: Vec<WindowPerformance> =
+            rpc.call("bsc_validatorPerformance", [Address::ZERO]).await.unwrap();
+        assert!(unknown.iter().all(|window| window.uptime.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_set_head_override() {
+        let head_override = HeadOverride::default();
+        let rpc =
+            BscAdminRpc::new(mainnet_provider(), head_override.clone(), PeerHeadTracker::default())
+                .into_rpc();
+        let pinned = B256::repeat_byte(0x11);
+
+        let () = rpc.call("bsc_setHeadOverride", [Some(pinned)]).await.unwrap();
+        assert_eq!(head_override.get(), Some(pinned));
+        let () = rpc.call("bsc_setHeadOverride", [None::<B256>]).await.unwrap();
+        assert_eq!(head_override.get(), None);
+    }
+
+    #[tokio::test]
+    async fn test_sync_status_and_node_info() {
+        let provider = mainnet_provider();
+        let peer_heads = PeerHeadTracker::default();
+        let rpc = BscAdminRpc::new(provider.clone(), HeadOverride::default(), peer_heads.clone())
+            .into_rpc();
+
+        // post-Maxwell epochs of 1000 blocks, the second one adds a validator
+        let timestamp = 1_751_250_600;
+        let validators = [1, 2, 3, 4].map(|byte| ValidatorInfo {
+            address: Address::repeat_byte(byte),
+            vote_address: VoteAddress::repeat_byte(byte),
+        });
+        let headers = [
+            Header { number: 51_998_999, timestamp, ..Default::default() },
+            epoch_header(51_999_000, timestamp, &validators[..3], 4),
+            Header { number: 51_999_999, timestamp, ..Default::default() },
+            epoch_header(52_000_000, timestamp, &validators, 4),
+            Header { number: 52_000_007, timestamp, ..Default::default() },
+        ];
+        for header in headers {
+            provider.add_header(header.hash_slow(), header);
+        }
+
+        let status: BscSyncStatus =
+            rpc.call("bsc_syncStatus", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(
+            status,
+            BscSyncStatus {
+                is_syncing: false,
+                current_block: 52_000_007,
+                highest_block: 52_000_007
+            }
+        );
+        let info: BscNodeInfo = rpc.call("bsc_nodeInfo", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(
+            info,
+            BscNodeInfo { chain_id: 56, hardfork: "Maxwell".to_string(), validator_count: Some(3) }
+        );
+
+        // a peer announcing a higher head puts the node into sync
+        let head =
+            PeerHead { number: 52_000_008, hash: B256::repeat_byte(0x08), difficulty: DIFF_INTURN };
+        peer_heads.on_new_block(PeerId::random(), head);
+        let status: BscSyncStatus =
+            rpc.call("bsc_syncStatus", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(
+            status,
+            BscSyncStatus {
+                is_syncing: true,
+                current_block: 52_000_007,
+                highest_block: 52_000_008
+            }
+        );
+
+        // importing the announced block completes the sync and switches to the new rotation
+        let header = Header { number: 52_000_008, timestamp, ..Default::default() };
+        provider.add_header(header.hash_slow(), header);
+        let status: BscSyncStatus =
+            rpc.call("bsc_syncStatus", EmptyServerParams::new()).await.unwrap();
+        assert!(!status.is_syncing);
+        let info: BscNodeInfo = rpc.call("bsc_nodeInfo", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(info.validator_count, Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_slashing() {
+        let slashing = SlashingListener::default();
+        let rpc = ParliaRpc::new(mainnet_provider(), slashing.clone(), ProposerArchive::default())
+            .into_rpc();
+        let mut subscription = rpc
+            .subscribe_unbounded("parlia_subscribeSlashing", EmptyServerParams::new())
+            .await
+            .unwrap();
+
+        let (validator, beneficiary) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let block = BlockNumHash::new(7, B256::repeat_byte(0x33));
+        slashing.on_slash(block, validator, beneficiary);
+
+        let (event, _) = subscription.next::<SlashEvent>().await.unwrap().unwrap();
+        assert_eq!(event, SlashEvent { block: 7, hash: block.hash, validator, beneficiary });
+    }
+
+    #[tokio::test]
+    async fn test_get_proposer() {
+        let provider = mainnet_provider();
+        let proposers = ProposerArchive::default();
+        let rpc = ParliaRpc::new(provider.clone(), SlashingListener::default(), proposers.clone())
+            .into_rpc();
+
+        let header = |number| Header { number, ..Default::default() };
+        for number in 1..=3 {
+            provider.add_header(header(number).hash_slow(), header(number));
+        }
+        let proposer = Address::repeat_byte(0x11);
+        let record = ProposerRecord::new(header(1).hash_slow(), proposer, DIFF_INTURN);
+        proposers.insert(1, record);
+        // recorded for a block that is no longer canonical
+        proposers.insert(2, ProposerRecord::new(B256::repeat_byte(0x22), proposer, DIFF_INTURN));
+
+        let get = |number: u64| rpc.call("parlia_getProposer", [BlockNumberOrTag::Number(number)]);
+        let found: Option<ProposerRecord> = get(1).await.unwrap();
+        assert_eq!(found, Some(record));
+        let stale: Option<ProposerRecord> = get(2).await.unwrap();
+        assert_eq!(stale, None);
+        let missing: Option<ProposerRecord> = get(3).await.unwrap();
+        assert_eq!(missing, None);
+
+        let err = get(4).await.unwrap_err();
+        assert!(matches!(err, MethodsError::JsonRpc(err) if err.code() == INVALID_PARAMS_CODE));
+    }
+
+    #[tokio::test]
+    async fn test_get_vote_address() {
+        let provider = mainnet_provider();
+        let rpc = ParliaRpc::new(
+            provider.clone(),
+            SlashingListener::default(),
+            ProposerArchive::default(),
+        )
+        .into_rpc();
+
+        // post-Maxwell epochs of 1000 blocks, the second one drops the first validator
+        let timestamp = 1_751_250_600;
+        let validators = [1, 2, 3, 4].map(|byte| ValidatorInfo {
+            address: Address::repeat_byte(byte),
+            vote_address: VoteAddress::repeat_byte(byte),
+        });
+        let headers = [
+            Header { number: 51_998_999, timestamp, ..Default::default() },
+            epoch_header(51_999_000, timestamp, &validators[..3], 4),
+            Header { number: 51_999_999, timestamp, ..Default::default() },
+            epoch_header(52_000_000, timestamp, &validators[1..], 4),
+            Header { number: 52_000_007, timestamp, ..Default::default() },
+            Header { number: 52_000_008, timestamp, ..Default::default() },
+        ];
+        for header in headers {
+            provider.add_header(header.hash_slow(), header);
+        }
+        let get = |number: u64, validator: ValidatorInfo| {
+            rpc.call::<_, VoteAddress>(
+                "parlia_getVoteAddress",
+                (BlockNumberOrTag::Number(number), validator.address),
+            )
+        };
+
+        assert_eq!(get(52_000_008, validators[3]).await.unwrap(), validators[3].vote_address);
+        let err = get(52_000_008, validators[0]).await.unwrap_err();
+        assert!(matches!(err, MethodsError::JsonRpc(err) if err.code() == INVALID_PARAMS_CODE));
+
+        // the validators of the previous epoch seal until half of them plus one had their turn
+        assert_eq!(get(52_000_007, validators[0]).await.unwrap(), validators[0].vote_address);
+        let err = get(52_000_007, validators[3]).await.unwrap_err();
+        assert!(matches!(err, MethodsError::JsonRpc(err) if err.code() == INVALID_PARAMS_CODE));
+    }
+
+    #[tokio::test]
+    async fn test_get_consensus_params() {
+        let provider = mainnet_provider();
+        let rpc = ParliaRpc::new(
+            provider.clone(),
+            SlashingListener::default(),
+            ProposerArchive::default(),
+        )
+        .into_rpc();
+        let validators = [1, 2, 3].map(|byte| ValidatorInfo {
+            address: Address::repeat_byte(byte),
+            vote_address: VoteAddress::repeat_byte(byte),
+        });
+
+        // after Luban but before Bohr, epochs of 200 blocks sealed one block per validator
+        let timestamp = 1_720_000_000;
+        let headers = [
+            Header { number: 39_998_999, timestamp, ..Default::default() },
+            Header { number: 39_999_499, timestamp, ..Default::default() },
+            epoch_header(39_999_800, timestamp, &validators, 0),
+            Header { number: 39_999_999, timestamp, ..Default::default() },
+            epoch_header(40_000_000, timestamp, &validators, 0),
+            Header { number: 40_000_011, timestamp, ..Default::default() },
+        ];
+        // since Maxwell, epochs of 1000 blocks sealed four blocks per validator
+        let timestamp = 1_751_250_600;
+        let bohr_headers = [
+            Header { number: 51_998_999, timestamp, ..Default::default() },
+            epoch_header(51_999_000, timestamp, &validators, 4),
+            Header { number: 51_999_999, timestamp, ..Default::default() },
+            epoch_header(52_000_000, timestamp, &validators, 4),
+            Header { number: 52_000_011, timestamp, ..Default::default() },
+        ];
+        for header in headers.into_iter().chain(bohr_headers) {
+            provider.add_header(header.hash_slow(), header);
+        }
+        let get = |number: u64| {
+            rpc.call::<_, ConsensusParams>(
+                "parlia_getConsensusParams",
+                [BlockNumberOrTag::Number(number)],
+            )
+        };
+
+        assert_eq!(
+            get(40_000_011).await.unwrap(),
+            ConsensusParams {
+                epoch_length: 200,
+                turn_length: 1,
+                block_interval_ms: 3_000,
+                is_luban: true,
+                is_bohr: false,
+                is_lorentz: false,
+                is_maxwell: false,
+            }
+        );
+        assert_eq!(
+            get(52_000_011).await.unwrap(),
+            ConsensusParams {
+                epoch_length: 1_000,
+                turn_length: 4,
+                block_interval_ms: 750,
+                is_luban: true,
+                is_bohr: true,
+                is_lorentz: true,
+                is_maxwell: true,
+            }
+        );
+
+        let err = get(52_000_012).await.unwrap_err();
+        assert!(matches!(err, MethodsError::JsonRpc(err) if err.code() == INVALID_PARAMS_CODE));
+    }
+
+    #[test]
+    fn test_upgrades_by_head() {
+        let chain_spec = BscChainSpec::from(bsc_mainnet());
+        let ForkCondition::Timestamp(feynman) =
+            chain_spec.bsc_fork_activation(BscHardfork::Feynman)
+        else {
+            panic!("Feynman is timestamp based");
+        };
+        // all block based forks are active by then
+        let header = |timestamp| Header { number: 40_000_000, timestamp, ..Default::default() };
+        let hardforks = |upgrades: Vec<HardforkUpgrades>| {
+            upgrades.into_iter().map(|upgrades| upgrades.hardfork).collect::<Vec<_>>()
+        };
+
+        // Feynman is upcoming until the head reaches it
+        let upcoming = upcoming_upgrades(&chain_spec, &header(feynman - 1)).unwrap();
+        assert_eq!(upcoming[0].hardfork, "Feynman");
+        let stake_hub =
+            upcoming[0].contracts.iter().find(|upgrade| upgrade.address == STAKE_HUB_CONTRACT);
+        assert!(stake_hub.is_some_and(|upgrade| upgrade.code_hash != B256::ZERO));
+        let upcoming = hardforks(upcoming_upgrades(&chain_spec, &header(feynman)).unwrap());
+        assert!(!upcoming.contains(&"Feynman".to_string()));
+        assert!(upcoming.contains(&"Maxwell".to_string()));
+
+        // the history only lists the forks of the fork block
+        let applied = upgrade_history(&chain_spec, &header(feynman), feynman - 3).unwrap();
+        assert_eq!(
+            applied[0].contracts,
+            system_contract_upgrades(&chain_spec, BscHardfork::Feynman).unwrap()
+        );
+        assert_eq!(hardforks(applied), ["Feynman", "FeynmanFix"]);
+        assert!(upgrade_history(&chain_spec, &header(feynman + 3), feynman).unwrap().is_empty());
+    }
+}